@@ -0,0 +1,192 @@
+//! Short Weierstrass points `y^2 = x^3 + b` in Jacobian coordinates, shared by
+//! G1 over Fp and G2 over the sextic twist in Fp2.
+
+use super::fields::{Field, Fp, Fp2};
+
+pub(crate) trait Curve: Copy {
+    type Base: Field;
+
+    fn b() -> Self::Base;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct G1Curve;
+
+impl Curve for G1Curve {
+    type Base = Fp;
+
+    fn b() -> Fp {
+        Fp::from_u64(3)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct G2Curve;
+
+impl Curve for G2Curve {
+    type Base = Fp2;
+
+    /// `3 / xi`, in Montgomery form.
+    fn b() -> Fp2 {
+        Fp2::new(
+            Fp::from_mont([
+                0x3bf938e377b802a8,
+                0x020b1b273633535d,
+                0x26b7edf049755260,
+                0x2514c6324384a86d,
+            ]),
+            Fp::from_mont([
+                0x38e7ecccd1dcff67,
+                0x65f0b37d93ce0d3e,
+                0xd749d0dd22ac00aa,
+                0x0141b9ce4a688d4d,
+            ]),
+        )
+    }
+}
+
+/// An affine point; `infinity` marks the point at infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Affine<C: Curve> {
+    pub(crate) x: C::Base,
+    pub(crate) y: C::Base,
+    pub(crate) infinity: bool,
+}
+
+impl<C: Curve> Affine<C> {
+    pub(crate) fn identity() -> Self {
+        Affine {
+            x: C::Base::zero(),
+            y: C::Base::zero(),
+            infinity: true,
+        }
+    }
+
+    pub(crate) fn new(x: C::Base, y: C::Base) -> Self {
+        Affine {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+
+    pub(crate) fn is_on_curve(&self) -> bool {
+        self.infinity || self.y.square() == self.x.square() * self.x + C::b()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Jacobian<C: Curve> {
+    x: C::Base,
+    y: C::Base,
+    z: C::Base,
+}
+
+impl<C: Curve> Jacobian<C> {
+    pub(crate) fn identity() -> Self {
+        Jacobian {
+            x: C::Base::one(),
+            y: C::Base::one(),
+            z: C::Base::zero(),
+        }
+    }
+
+    pub(crate) fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub(crate) fn to_affine(self) -> Affine<C> {
+        if self.is_identity() {
+            return Affine::identity();
+        }
+        let z_inv = self.z.inverse();
+        let z_inv2 = z_inv.square();
+        Affine::new(self.x * z_inv2, self.y * z_inv2 * z_inv)
+    }
+
+    // dbl-2009-l
+    pub(crate) fn double(&self) -> Self {
+        if self.is_identity() {
+            return *self;
+        }
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = ((self.x + b).square() - a - c).double();
+        let e = a.double() + a;
+        let f = e.square();
+        let x3 = f - d.double();
+        let c8 = c.double().double().double();
+        Jacobian {
+            x: x3,
+            y: e * (d - x3) - c8,
+            z: (self.y * self.z).double(),
+        }
+    }
+
+    // add-2007-bl
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        if self.is_identity() {
+            return *other;
+        }
+        if other.is_identity() {
+            return *self;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x * z2z2;
+        let u2 = other.x * z1z1;
+        let s1 = self.y * other.z * z2z2;
+        let s2 = other.y * self.z * z1z1;
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double()
+            } else {
+                Self::identity()
+            };
+        }
+        let h = u2 - u1;
+        let i = h.double().square();
+        let j = h * i;
+        let r = (s2 - s1).double();
+        let v = u1 * i;
+        let x3 = r.square() - j - v.double();
+        Jacobian {
+            x: x3,
+            y: r * (v - x3) - (s1 * j).double(),
+            z: ((self.z + other.z).square() - z1z1 - z2z2) * h,
+        }
+    }
+
+    /// Multiplies by a big-endian integer of any length.
+    pub(crate) fn mul_be(&self, scalar: &[u8]) -> Self {
+        let mut acc = Self::identity();
+        for byte in scalar {
+            for bit in (0..8).rev() {
+                acc = acc.double();
+                if (byte >> bit) & 1 == 1 {
+                    acc = acc.add(self);
+                }
+            }
+        }
+        acc
+    }
+}
+
+impl<C: Curve> From<Affine<C>> for Jacobian<C> {
+    fn from(p: Affine<C>) -> Self {
+        if p.infinity {
+            return Self::identity();
+        }
+        Jacobian {
+            x: p.x,
+            y: p.y,
+            z: C::Base::one(),
+        }
+    }
+}
+
+pub(crate) type G1Affine = Affine<G1Curve>;
+pub(crate) type G2Affine = Affine<G2Curve>;
+pub(crate) type G1Projective = Jacobian<G1Curve>;
+pub(crate) type G2Projective = Jacobian<G2Curve>;
@@ -0,0 +1,539 @@
+//! The BN254 field tower: Fp, Fp2 = Fp[i]/(i^2 + 1), Fp6 = Fp2[v]/(v^3 - xi)
+//! and Fp12 = Fp6[w]/(w^2 - v), with xi = 9 + i.
+//!
+//! Fp elements are kept in Montgomery form over four 64-bit limbs.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+/// The base field modulus, little-endian limbs.
+pub(crate) const MODULUS: [u64; 4] = [
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// `-p^-1 mod 2^64`.
+const INV: u64 = 0x87d20782e4866389;
+
+/// `2^256 mod p`, i.e. one in Montgomery form.
+const R: [u64; 4] = [
+    0xd35d438dc58f0d9d,
+    0x0a78eb28f5c70b3d,
+    0x666ea36f7879462c,
+    0x0e0a77c19a07df2f,
+];
+
+/// `2^512 mod p`, used to convert into Montgomery form.
+const R2: [u64; 4] = [
+    0xf32cfc5b538afa89,
+    0xb5e71911d44501fb,
+    0x47ab1eff0a417ff6,
+    0x06d89f71cab8351f,
+];
+
+pub(crate) trait Field:
+    Copy + Eq + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    /// Returns zero for zero.
+    fn inverse(&self) -> Self;
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    fn double(&self) -> Self {
+        *self + *self
+    }
+
+    /// `self^exp` for a little-endian limb exponent.
+    fn pow(&self, exp: &[u64]) -> Self {
+        let mut res = Self::one();
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                res = res.square();
+                if (limb >> bit) & 1 == 1 {
+                    res = res * *self;
+                }
+            }
+        }
+        res
+    }
+}
+
+fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let t = (a as u128).wrapping_sub(b as u128 + (borrow >> 63) as u128);
+    (t as u64, (t >> 64) as u64)
+}
+
+fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + b as u128 * c as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+fn geq_modulus(a: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != MODULUS[i] {
+            return a[i] > MODULUS[i];
+        }
+    }
+    true
+}
+
+fn sub_modulus(a: &mut [u64; 4]) {
+    let mut borrow = 0;
+    for i in 0..4 {
+        let (v, b) = sbb(a[i], MODULUS[i], borrow);
+        a[i] = v;
+        borrow = b;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Fp(pub(crate) [u64; 4]);
+
+impl Fp {
+    /// Wraps limbs that are already in Montgomery form.
+    pub(crate) const fn from_mont(limbs: [u64; 4]) -> Self {
+        Fp(limbs)
+    }
+
+    /// Parses a big-endian integer, rejecting values `>= p`.
+    pub(crate) fn from_bytes_be(bytes: &[u8; 32]) -> Option<Self> {
+        let mut limbs = [0_u64; 4];
+        for (i, chunk) in bytes.chunks(8).rev().enumerate() {
+            let mut word = [0_u8; 8];
+            word.copy_from_slice(chunk);
+            limbs[i] = u64::from_be_bytes(word);
+        }
+        if geq_modulus(&limbs) {
+            return None;
+        }
+        Some(Fp(limbs) * Fp(R2))
+    }
+
+    pub(crate) fn to_bytes_be(self) -> [u8; 32] {
+        let canonical = self * Fp([1, 0, 0, 0]);
+        let mut out = [0_u8; 32];
+        for (i, chunk) in out.chunks_mut(8).rev().enumerate() {
+            chunk.copy_from_slice(&canonical.0[i].to_be_bytes());
+        }
+        out
+    }
+
+    pub(crate) fn from_u64(v: u64) -> Self {
+        Fp([v, 0, 0, 0]) * Fp(R2)
+    }
+}
+
+impl Add for Fp {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut out = [0_u64; 4];
+        let mut carry = 0;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (v, c) = adc(self.0[i], other.0[i], carry);
+            *limb = v;
+            carry = c;
+        }
+        // p < 2^254, so the sum never overflows 256 bits.
+        if geq_modulus(&out) {
+            sub_modulus(&mut out);
+        }
+        Fp(out)
+    }
+}
+
+impl Sub for Fp {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut out = [0_u64; 4];
+        let mut borrow = 0;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let (v, b) = sbb(self.0[i], other.0[i], borrow);
+            *limb = v;
+            borrow = b;
+        }
+        if borrow != 0 {
+            let mut carry = 0;
+            for i in 0..4 {
+                let (v, c) = adc(out[i], MODULUS[i], carry);
+                out[i] = v;
+                carry = c;
+            }
+        }
+        Fp(out)
+    }
+}
+
+impl Neg for Fp {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Fp::zero() - self
+    }
+}
+
+impl Mul for Fp {
+    type Output = Self;
+
+    // CIOS Montgomery multiplication.
+    fn mul(self, other: Self) -> Self {
+        let (a, b) = (self.0, other.0);
+        let mut t = [0_u64; 6];
+        for b_i in b {
+            let mut carry = 0;
+            for j in 0..4 {
+                let (v, c) = mac(t[j], a[j], b_i, carry);
+                t[j] = v;
+                carry = c;
+            }
+            let (v, c) = adc(t[4], carry, 0);
+            t[4] = v;
+            t[5] = c;
+
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut carry) = mac(t[0], m, MODULUS[0], 0);
+            for j in 1..4 {
+                let (v, c) = mac(t[j], m, MODULUS[j], carry);
+                t[j - 1] = v;
+                carry = c;
+            }
+            let (v, c) = adc(t[4], carry, 0);
+            t[3] = v;
+            t[4] = t[5] + c;
+        }
+        let mut out = [t[0], t[1], t[2], t[3]];
+        if t[4] != 0 || geq_modulus(&out) {
+            sub_modulus(&mut out);
+        }
+        Fp(out)
+    }
+}
+
+impl Field for Fp {
+    fn zero() -> Self {
+        Fp([0; 4])
+    }
+
+    fn one() -> Self {
+        Fp(R)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    fn inverse(&self) -> Self {
+        let mut exp = MODULUS;
+        exp[0] -= 2;
+        self.pow(&exp)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Fp2 {
+    pub(crate) c0: Fp,
+    pub(crate) c1: Fp,
+}
+
+impl Fp2 {
+    pub(crate) const fn new(c0: Fp, c1: Fp) -> Self {
+        Fp2 { c0, c1 }
+    }
+
+    /// Multiplies by `xi = 9 + i`.
+    pub(crate) fn mul_by_nonresidue(&self) -> Self {
+        let nine = |a: Fp| {
+            let a2 = a.double();
+            let a8 = a2.double().double();
+            a8 + a
+        };
+        Fp2 {
+            c0: nine(self.c0) - self.c1,
+            c1: nine(self.c1) + self.c0,
+        }
+    }
+
+    /// The `p`-power Frobenius map, i.e. complex conjugation.
+    pub(crate) fn conjugate(&self) -> Self {
+        Fp2 {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+
+    pub(crate) fn scale(&self, k: Fp) -> Self {
+        Fp2 {
+            c0: self.c0 * k,
+            c1: self.c1 * k,
+        }
+    }
+}
+
+impl Add for Fp2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Fp2 {
+            c0: self.c0 + other.c0,
+            c1: self.c1 + other.c1,
+        }
+    }
+}
+
+impl Sub for Fp2 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Fp2 {
+            c0: self.c0 - other.c0,
+            c1: self.c1 - other.c1,
+        }
+    }
+}
+
+impl Neg for Fp2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Fp2 {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Mul for Fp2 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let aa = self.c0 * other.c0;
+        let bb = self.c1 * other.c1;
+        let cross = (self.c0 + self.c1) * (other.c0 + other.c1);
+        Fp2 {
+            c0: aa - bb,
+            c1: cross - aa - bb,
+        }
+    }
+}
+
+impl Field for Fp2 {
+    fn zero() -> Self {
+        Fp2::new(Fp::zero(), Fp::zero())
+    }
+
+    fn one() -> Self {
+        Fp2::new(Fp::one(), Fp::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn inverse(&self) -> Self {
+        let norm_inv = (self.c0.square() + self.c1.square()).inverse();
+        Fp2 {
+            c0: self.c0 * norm_inv,
+            c1: -(self.c1 * norm_inv),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Fp6 {
+    pub(crate) c0: Fp2,
+    pub(crate) c1: Fp2,
+    pub(crate) c2: Fp2,
+}
+
+impl Fp6 {
+    /// Multiplies by `v`.
+    fn mul_by_nonresidue(&self) -> Self {
+        Fp6 {
+            c0: self.c2.mul_by_nonresidue(),
+            c1: self.c0,
+            c2: self.c1,
+        }
+    }
+}
+
+impl Add for Fp6 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Fp6 {
+            c0: self.c0 + other.c0,
+            c1: self.c1 + other.c1,
+            c2: self.c2 + other.c2,
+        }
+    }
+}
+
+impl Sub for Fp6 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Fp6 {
+            c0: self.c0 - other.c0,
+            c1: self.c1 - other.c1,
+            c2: self.c2 - other.c2,
+        }
+    }
+}
+
+impl Neg for Fp6 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Fp6 {
+            c0: -self.c0,
+            c1: -self.c1,
+            c2: -self.c2,
+        }
+    }
+}
+
+impl Mul for Fp6 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+        let (b0, b1, b2) = (other.c0, other.c1, other.c2);
+        Fp6 {
+            c0: a0 * b0 + (a1 * b2 + a2 * b1).mul_by_nonresidue(),
+            c1: a0 * b1 + a1 * b0 + (a2 * b2).mul_by_nonresidue(),
+            c2: a0 * b2 + a1 * b1 + a2 * b0,
+        }
+    }
+}
+
+impl Field for Fp6 {
+    fn zero() -> Self {
+        Fp6::default()
+    }
+
+    fn one() -> Self {
+        Fp6 {
+            c0: Fp2::one(),
+            c1: Fp2::zero(),
+            c2: Fp2::zero(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    fn inverse(&self) -> Self {
+        let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+        let t0 = a0.square() - (a1 * a2).mul_by_nonresidue();
+        let t1 = a2.square().mul_by_nonresidue() - a0 * a1;
+        let t2 = a1.square() - a0 * a2;
+        let det = a0 * t0 + (a2 * t1 + a1 * t2).mul_by_nonresidue();
+        let det_inv = det.inverse();
+        Fp6 {
+            c0: t0 * det_inv,
+            c1: t1 * det_inv,
+            c2: t2 * det_inv,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Fp12 {
+    pub(crate) c0: Fp6,
+    pub(crate) c1: Fp6,
+}
+
+impl Fp12 {
+    /// The `p^6`-power Frobenius map.
+    pub(crate) fn conjugate(&self) -> Self {
+        Fp12 {
+            c0: self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Add for Fp12 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Fp12 {
+            c0: self.c0 + other.c0,
+            c1: self.c1 + other.c1,
+        }
+    }
+}
+
+impl Sub for Fp12 {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Fp12 {
+            c0: self.c0 - other.c0,
+            c1: self.c1 - other.c1,
+        }
+    }
+}
+
+impl Neg for Fp12 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Fp12 {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl Mul for Fp12 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let aa = self.c0 * other.c0;
+        let bb = self.c1 * other.c1;
+        let cross = (self.c0 + self.c1) * (other.c0 + other.c1);
+        Fp12 {
+            c0: aa + bb.mul_by_nonresidue(),
+            c1: cross - aa - bb,
+        }
+    }
+}
+
+impl Field for Fp12 {
+    fn zero() -> Self {
+        Fp12::default()
+    }
+
+    fn one() -> Self {
+        Fp12 {
+            c0: Fp6::one(),
+            c1: Fp6::zero(),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn inverse(&self) -> Self {
+        let t = (self.c0.square() - self.c1.square().mul_by_nonresidue()).inverse();
+        Fp12 {
+            c0: self.c0 * t,
+            c1: -(self.c1 * t),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+//! The optimal ate pairing on BN254.
+//!
+//! The Miller loop runs in affine coordinates on the twist; it is not the
+//! fastest formulation, but it keeps the line evaluation easy to check
+//! against the textbook and is plenty for precompile-sized inputs.
+
+use super::curve::{G1Affine, G2Affine};
+use super::fields::{Field, Fp, Fp12, Fp2, Fp6};
+
+/// `6u + 2` for the BN parameter `u = 4965661367192848881`.
+const ATE_LOOP_COUNT: u128 = 0x19d797039be763ba8;
+const ATE_LOOP_BITS: u32 = 128 - ATE_LOOP_COUNT.leading_zeros();
+
+/// `(p^2 + 1) * (p^4 - p^2 + 1) / r`, little-endian limbs. Applied after the
+/// easy `p^6 - 1` part of the final exponentiation.
+const FINAL_EXP: [u64; 20] = [
+    0x5250a54036e3f812,
+    0xa5635f1596789051,
+    0xd1138bf54d5bd1d4,
+    0xa8ce2533be36c7a2,
+    0x94f69f6b84e09bf6,
+    0x42ad1f5e50ef3644,
+    0x0fcc420e48c3454c,
+    0x758e4408ecc9952c,
+    0xc901bf1887c6042c,
+    0xa733cd65b14bb3b5,
+    0xdf6d76bdcf51b0d8,
+    0xca64c0fd82eb59e1,
+    0x1d2e5726e39276a1,
+    0xc2d1ea74a391cae9,
+    0x07409206c82d647e,
+    0x051c6d1aa5afdd17,
+    0xb37f601919667af5,
+    0x150e578c5084015b,
+    0xfbdea556c23998e4,
+    0x000fd14cc52f5b83,
+];
+
+/// `xi^((p - 1) / 3)`, scales `x` under the Frobenius map on the twist.
+const FROB_X: Fp2 = Fp2::new(
+    Fp::from_mont([
+        0xb5773b104563ab30,
+        0x347f91c8a9aa6454,
+        0x7a007127242e0991,
+        0x1956bcd8118214ec,
+    ]),
+    Fp::from_mont([
+        0x6e849f1ea0aa4757,
+        0xaa1c7b6d89f89141,
+        0xb6e713cdfae0ca3a,
+        0x26694fbb4e82ebc3,
+    ]),
+);
+
+/// `xi^((p - 1) / 2)`, scales `y` under the Frobenius map on the twist.
+const FROB_Y: Fp2 = Fp2::new(
+    Fp::from_mont([
+        0xe4bbdd0c2936b629,
+        0xbb30f162e133bacb,
+        0x31a9d1b6f9645366,
+        0x253570bea500f8dd,
+    ]),
+    Fp::from_mont([
+        0xa1d77ce45ffe77c7,
+        0x07affd117826d1db,
+        0x6d16bd27bb7edc6b,
+        0x2c87200285defecc,
+    ]),
+);
+
+/// `xi^((p^2 - 1) / 3)`, which lies in Fp; the matching `y` factor is `-1`.
+const FROB2_X: Fp2 = Fp2::new(
+    Fp::from_mont([
+        0x3350c88e13e80b9c,
+        0x7dce557cdb5e56b9,
+        0x6001b4b8b615564a,
+        0x2682e617020217e0,
+    ]),
+    Fp::from_mont([0, 0, 0, 0]),
+);
+
+/// Evaluates at `p` the line of slope `lambda` through `t`, with the twist
+/// mapped into Fp12 as `(x w^2, y w^3)`:
+/// `y_P - lambda x_P w + (lambda x_T - y_T) w^3`.
+fn line(lambda: Fp2, t: &G2Affine, p: &G1Affine) -> Fp12 {
+    Fp12 {
+        c0: Fp6 {
+            c0: Fp2::new(p.y, Fp::zero()),
+            c1: Fp2::zero(),
+            c2: Fp2::zero(),
+        },
+        c1: Fp6 {
+            c0: -lambda.scale(p.x),
+            c1: lambda * t.x - t.y,
+            c2: Fp2::zero(),
+        },
+    }
+}
+
+/// Replaces `t` with `t + q` and multiplies `f` by the line through them.
+/// Vertical lines lie in a proper subfield and are wiped out by the final
+/// exponentiation, so they are skipped.
+fn add_step(f: &mut Fp12, t: &mut G2Affine, q: &G2Affine, p: &G1Affine) {
+    if t.infinity {
+        *t = *q;
+        return;
+    }
+    let lambda = if t.x == q.x {
+        if t.y != q.y || t.y.is_zero() {
+            *t = G2Affine::identity();
+            return;
+        }
+        let x2 = t.x.square();
+        (x2.double() + x2) * t.y.double().inverse()
+    } else {
+        (q.y - t.y) * (q.x - t.x).inverse()
+    };
+    *f = *f * line(lambda, t, p);
+    let x3 = lambda.square() - t.x - q.x;
+    let y3 = lambda * (t.x - x3) - t.y;
+    *t = G2Affine::new(x3, y3);
+}
+
+fn miller_loop(p: &G1Affine, q: &G2Affine) -> Fp12 {
+    let mut f = Fp12::one();
+    let mut t = *q;
+    for i in (0..ATE_LOOP_BITS - 1).rev() {
+        f = f.square();
+        let tt = t;
+        add_step(&mut f, &mut t, &tt, p);
+        if (ATE_LOOP_COUNT >> i) & 1 == 1 {
+            add_step(&mut f, &mut t, q, p);
+        }
+    }
+
+    let q1 = G2Affine::new(q.x.conjugate() * FROB_X, q.y.conjugate() * FROB_Y);
+    let q2 = G2Affine::new(q.x * FROB2_X, q.y);
+    add_step(&mut f, &mut t, &q1, p);
+    add_step(&mut f, &mut t, &q2, p);
+    f
+}
+
+fn final_exponentiation(f: &Fp12) -> Fp12 {
+    let f1 = f.conjugate() * f.inverse();
+    f1.pow(&FINAL_EXP)
+}
+
+/// Computes the product of pairings `prod e(p_i, q_i)`. Pairs with a point
+/// at infinity contribute one.
+pub(crate) fn multi_pairing(pairs: &[(G1Affine, G2Affine)]) -> Fp12 {
+    let f = pairs
+        .iter()
+        .filter(|(p, q)| !p.infinity && !q.infinity)
+        .fold(Fp12::one(), |acc, (p, q)| acc * miller_loop(p, q));
+    final_exponentiation(&f)
+}
@@ -0,0 +1,460 @@
+//! The BLS12-381 precompiles from EIP-2537.
+//!
+//! Each function takes the raw call data and returns the raw output, or an
+//! error when the precompile must fail (consuming all gas). Gas accounting is
+//! left to the caller.
+//!
+//! Encodings: an Fp element is 64 bytes, 16 zero bytes followed by the
+//! big-endian value, which must be below the field modulus. Fp2 elements are
+//! `c0 || c1`. Points are `x || y` with the point at infinity encoded as all
+//! zeros. Scalars are 32-byte big-endian integers and need not be reduced.
+
+use crate::bindings::*;
+use crate::points::{G1Point, G2Point, Gt, Scalar};
+use crate::Error;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ptr;
+use core::slice::ChunksExact;
+
+pub const G1ADD_ADDRESS: u64 = 0x0b;
+pub const G1MSM_ADDRESS: u64 = 0x0c;
+pub const G2ADD_ADDRESS: u64 = 0x0d;
+pub const G2MSM_ADDRESS: u64 = 0x0e;
+pub const PAIRING_ADDRESS: u64 = 0x0f;
+pub const MAP_FP_TO_G1_ADDRESS: u64 = 0x10;
+pub const MAP_FP2_TO_G2_ADDRESS: u64 = 0x11;
+
+const PADDED_FP_LENGTH: usize = 64;
+const PADDING_LENGTH: usize = 16;
+const PADDED_FP2_LENGTH: usize = 2 * PADDED_FP_LENGTH;
+const G1_LENGTH: usize = 2 * PADDED_FP_LENGTH;
+const G2_LENGTH: usize = 2 * PADDED_FP2_LENGTH;
+const SCALAR_LENGTH: usize = 32;
+const G1_MSM_PAIR_LENGTH: usize = G1_LENGTH + SCALAR_LENGTH;
+const G2_MSM_PAIR_LENGTH: usize = G2_LENGTH + SCALAR_LENGTH;
+const PAIRING_PAIR_LENGTH: usize = G1_LENGTH + G2_LENGTH;
+
+/// The base field modulus, big-endian.
+const MODULUS: [u8; 48] = [
+    0x1a, 0x01, 0x11, 0xea, 0x39, 0x7f, 0xe6, 0x9a, 0x4b, 0x1b, 0xa7, 0xb6, 0x43, 0x4b, 0xac, 0xd7,
+    0x64, 0x77, 0x4b, 0x84, 0xf3, 0x85, 0x12, 0xbf, 0x67, 0x30, 0xd2, 0xa0, 0xf6, 0xb0, 0xf6, 0x24,
+    0x1e, 0xab, 0xff, 0xfe, 0xb1, 0x53, 0xff, 0xff, 0xb9, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xaa, 0xab,
+];
+
+/// Dispatches on the precompile address; `None` if `address` isn't one of the
+/// EIP-2537 precompiles.
+pub fn run(address: u64, input: &[u8]) -> Option<Result<Vec<u8>, Error>> {
+    Some(match address {
+        G1ADD_ADDRESS => g1_add(input),
+        G1MSM_ADDRESS => g1_msm(input),
+        G2ADD_ADDRESS => g2_add(input),
+        G2MSM_ADDRESS => g2_msm(input),
+        PAIRING_ADDRESS => pairing(input),
+        MAP_FP_TO_G1_ADDRESS => map_fp_to_g1(input),
+        MAP_FP2_TO_G2_ADDRESS => map_fp2_to_g2(input),
+        _ => return None,
+    })
+}
+
+fn decode_fp(input: &[u8]) -> Result<blst_fp, Error> {
+    let (padding, value) = input.split_at(PADDING_LENGTH);
+    if padding.iter().any(|b| *b != 0) {
+        return Err(Error::BadEncoding);
+    }
+    if value >= &MODULUS[..] {
+        return Err(Error::BadEncoding);
+    }
+    let mut fp = blst_fp::default();
+    unsafe { blst_fp_from_bendian(&mut fp, value.as_ptr()) };
+    Ok(fp)
+}
+
+fn decode_fp2(input: &[u8]) -> Result<blst_fp2, Error> {
+    Ok(blst_fp2 {
+        fp: [
+            decode_fp(&input[..PADDED_FP_LENGTH])?,
+            decode_fp(&input[PADDED_FP_LENGTH..])?,
+        ],
+    })
+}
+
+fn encode_fp(fp: &blst_fp, out: &mut [u8]) {
+    unsafe { blst_bendian_from_fp(out[PADDING_LENGTH..].as_mut_ptr(), fp) };
+}
+
+fn encode_fp2(fp2: &blst_fp2, out: &mut [u8]) {
+    encode_fp(&fp2.fp[0], &mut out[..PADDED_FP_LENGTH]);
+    encode_fp(&fp2.fp[1], &mut out[PADDED_FP_LENGTH..]);
+}
+
+macro_rules! point_codec {
+    (
+        $decode:ident,
+        $encode:ident,
+        $group:ident,
+        $affine:ident,
+        $len:expr,
+        $coord_len:expr,
+        $decode_coord:ident,
+        $encode_coord:ident,
+        $on_curve:ident,
+        $in_group:ident
+    ) => {
+        fn $decode(input: &[u8], subgroup_check: bool) -> Result<$group, Error> {
+            if input.iter().all(|b| *b == 0) {
+                return Ok($group::identity());
+            }
+            let p = $affine {
+                x: $decode_coord(&input[..$coord_len])?,
+                y: $decode_coord(&input[$coord_len..$len])?,
+            };
+            if !unsafe { $on_curve(&p) } {
+                return Err(Error::PointNotOnCurve);
+            }
+            if subgroup_check && !unsafe { $in_group(&p) } {
+                return Err(Error::PointNotInGroup);
+            }
+            Ok($group::from_affine(&p))
+        }
+
+        fn $encode(p: &$group) -> Vec<u8> {
+            let mut out = vec![0_u8; $len];
+            if !p.is_identity() {
+                let p = p.to_affine();
+                $encode_coord(&p.x, &mut out[..$coord_len]);
+                $encode_coord(&p.y, &mut out[$coord_len..]);
+            }
+            out
+        }
+    };
+}
+
+point_codec!(
+    decode_g1,
+    encode_g1,
+    G1Point,
+    blst_p1_affine,
+    G1_LENGTH,
+    PADDED_FP_LENGTH,
+    decode_fp,
+    encode_fp,
+    blst_p1_affine_on_curve,
+    blst_p1_affine_in_g1
+);
+
+point_codec!(
+    decode_g2,
+    encode_g2,
+    G2Point,
+    blst_p2_affine,
+    G2_LENGTH,
+    PADDED_FP2_LENGTH,
+    decode_fp2,
+    encode_fp2,
+    blst_p2_affine_on_curve,
+    blst_p2_affine_in_g2
+);
+
+/// Splits MSM/pairing input into its fixed-size pairs; at least one is required.
+fn split_pairs(input: &[u8], pair_len: usize) -> Result<ChunksExact<'_, u8>, Error> {
+    let pairs = input.chunks_exact(pair_len);
+    if input.is_empty() || !pairs.remainder().is_empty() {
+        return Err(Error::InvalidInputLength);
+    }
+    Ok(pairs)
+}
+
+fn decode_scalar(input: &[u8]) -> Scalar {
+    Scalar::from_bytes_be_reduced(input)
+}
+
+/// G1ADD: adds two G1 points. No subgroup check is performed.
+pub fn g1_add(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != 2 * G1_LENGTH {
+        return Err(Error::InvalidInputLength);
+    }
+    let a = decode_g1(&input[..G1_LENGTH], false)?;
+    let b = decode_g1(&input[G1_LENGTH..], false)?;
+    Ok(encode_g1(&(a + b)))
+}
+
+/// G2ADD: adds two G2 points. No subgroup check is performed.
+pub fn g2_add(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != 2 * G2_LENGTH {
+        return Err(Error::InvalidInputLength);
+    }
+    let a = decode_g2(&input[..G2_LENGTH], false)?;
+    let b = decode_g2(&input[G2_LENGTH..], false)?;
+    Ok(encode_g2(&(a + b)))
+}
+
+/// G1MSM: `sum(s_i * P_i)` over `(P_i, s_i)` pairs; every point must be in G1.
+pub fn g1_msm(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let chunks = split_pairs(input, G1_MSM_PAIR_LENGTH)?;
+    let mut points = Vec::with_capacity(chunks.len());
+    let mut scalars = Vec::with_capacity(points.capacity());
+    for pair in chunks {
+        points.push(decode_g1(&pair[..G1_LENGTH], true)?);
+        scalars.push(decode_scalar(&pair[G1_LENGTH..]));
+    }
+    Ok(encode_g1(&G1Point::msm(&points, &scalars)?))
+}
+
+/// G2MSM: `sum(s_i * Q_i)` over `(Q_i, s_i)` pairs; every point must be in G2.
+pub fn g2_msm(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let chunks = split_pairs(input, G2_MSM_PAIR_LENGTH)?;
+    let mut points = Vec::with_capacity(chunks.len());
+    let mut scalars = Vec::with_capacity(points.capacity());
+    for pair in chunks {
+        points.push(decode_g2(&pair[..G2_LENGTH], true)?);
+        scalars.push(decode_scalar(&pair[G2_LENGTH..]));
+    }
+    Ok(encode_g2(&G2Point::msm(&points, &scalars)?))
+}
+
+/// PAIRING: returns 1 (as a 32-byte word) if `prod e(P_i, Q_i)` is one, 0
+/// otherwise. Every point must be in its subgroup.
+pub fn pairing(input: &[u8]) -> Result<Vec<u8>, Error> {
+    let chunks = split_pairs(input, PAIRING_PAIR_LENGTH)?;
+    let mut pairs = Vec::with_capacity(chunks.len());
+    for pair in chunks {
+        let p = decode_g1(&pair[..G1_LENGTH], true)?;
+        let q = decode_g2(&pair[G1_LENGTH..], true)?;
+        pairs.push((p, q));
+    }
+    let mut out = vec![0_u8; 32];
+    out[31] = Gt::multi_pairing(&pairs).is_one() as u8;
+    Ok(out)
+}
+
+/// MAP_FP_TO_G1: the simplified SWU map followed by cofactor clearing.
+pub fn map_fp_to_g1(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != PADDED_FP_LENGTH {
+        return Err(Error::InvalidInputLength);
+    }
+    let u = decode_fp(input)?;
+    let mut p = blst_p1::default();
+    unsafe { blst_map_to_g1(&mut p, &u, ptr::null()) };
+    Ok(encode_g1(&G1Point(p)))
+}
+
+/// MAP_FP2_TO_G2: the simplified SWU map followed by cofactor clearing.
+pub fn map_fp2_to_g2(input: &[u8]) -> Result<Vec<u8>, Error> {
+    if input.len() != PADDED_FP2_LENGTH {
+        return Err(Error::InvalidInputLength);
+    }
+    let u = decode_fp2(input)?;
+    let mut p = blst_p2::default();
+    unsafe { blst_map_to_g2(&mut p, &u, ptr::null()) };
+    Ok(encode_g2(&G2Point(p)))
+}
+
+// The arithmetic precompiles are checked against the generator encodings and
+// the group law, and the map precompiles against the RFC 9380 vectors that
+// ship with blst. The upstream EIP-2537 JSON vectors are not vendored yet, so
+// `test_eip2537_vectors` is ignored until they are added under
+// `tests/eip2537/` (see the README there).
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vectors;
+
+    const G1_GENERATOR: &str = "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1";
+    const G2_GENERATOR: &str = "00000000000000000000000000000000024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80000000000000000000000000000000013e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e000000000000000000000000000000000ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801000000000000000000000000000000000606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be";
+
+    fn scalar(v: u64) -> [u8; 32] {
+        let mut out = [0_u8; 32];
+        out[24..].copy_from_slice(&v.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn test_generator_encoding() {
+        assert_eq!(hex::encode(encode_g1(&G1Point::generator())), G1_GENERATOR);
+        assert_eq!(hex::encode(encode_g2(&G2Point::generator())), G2_GENERATOR);
+        assert_eq!(encode_g1(&G1Point::identity()), vec![0; 128]);
+    }
+
+    #[test]
+    fn test_add() {
+        let g1 = hex::decode(G1_GENERATOR).unwrap();
+        let g2 = hex::decode(G2_GENERATOR).unwrap();
+        let two_g1 = encode_g1(&(G1Point::generator() * Scalar::from_u64(2)));
+        let two_g2 = encode_g2(&(G2Point::generator() * Scalar::from_u64(2)));
+
+        assert_eq!(g1_add(&[&g1[..], &g1[..]].concat()).unwrap(), two_g1);
+        assert_eq!(g1_add(&[&g1[..], &[0; 128][..]].concat()).unwrap(), g1);
+        assert_eq!(g2_add(&[&g2[..], &g2[..]].concat()).unwrap(), two_g2);
+        assert_eq!(g2_add(&[&[0; 256][..], &g2[..]].concat()).unwrap(), g2);
+
+        let neg = encode_g1(&-G1Point::generator());
+        assert_eq!(g1_add(&[&g1[..], &neg[..]].concat()).unwrap(), vec![0; 128]);
+        assert_eq!(g1_add(&g1), Err(Error::InvalidInputLength));
+    }
+
+    #[test]
+    fn test_msm() {
+        let g1 = hex::decode(G1_GENERATOR).unwrap();
+        let g2 = hex::decode(G2_GENERATOR).unwrap();
+        let input = [&g1[..], &scalar(5), &g1[..], &scalar(7)].concat();
+        assert_eq!(
+            g1_msm(&input).unwrap(),
+            encode_g1(&(G1Point::generator() * Scalar::from_u64(12)))
+        );
+        let input = [&g2[..], &scalar(3)].concat();
+        assert_eq!(
+            g2_msm(&input).unwrap(),
+            encode_g2(&(G2Point::generator() * Scalar::from_u64(3)))
+        );
+
+        // Scalars are taken modulo the group order.
+        let r = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+            .unwrap();
+        assert_eq!(g1_msm(&[&g1[..], &r[..]].concat()).unwrap(), vec![0; 128]);
+
+        assert_eq!(g1_msm(&[]), Err(Error::InvalidInputLength));
+        assert_eq!(g2_msm(&g2), Err(Error::InvalidInputLength));
+    }
+
+    #[test]
+    fn test_pairing() {
+        let a = G1Point::generator() * Scalar::from_u64(6);
+        let b = G2Point::generator();
+        let c = -G1Point::generator();
+        let d = G2Point::generator() * Scalar::from_u64(6);
+        let input = [encode_g1(&a), encode_g2(&b), encode_g1(&c), encode_g2(&d)].concat();
+        assert_eq!(pairing(&input).unwrap()[31], 1);
+        assert_eq!(pairing(&input[..384]).unwrap(), vec![0; 32]);
+        assert_eq!(pairing(&[0; 384]).unwrap()[31], 1);
+        assert_eq!(pairing(&[]), Err(Error::InvalidInputLength));
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let mut g1 = hex::decode(G1_GENERATOR).unwrap();
+        let zero = [0_u8; 128];
+
+        // Non-zero padding.
+        g1[0] = 1;
+        assert_eq!(
+            g1_add(&[&g1[..], &zero[..]].concat()),
+            Err(Error::BadEncoding)
+        );
+        g1[0] = 0;
+
+        // Coordinate equal to the modulus.
+        let mut bad = g1.clone();
+        bad[16..64].copy_from_slice(&MODULUS);
+        assert_eq!(
+            g1_add(&[&bad[..], &zero[..]].concat()),
+            Err(Error::BadEncoding)
+        );
+        assert_eq!(map_fp_to_g1(&bad[..64]), Err(Error::BadEncoding));
+
+        // Off the curve.
+        g1[127] ^= 1;
+        assert_eq!(
+            g1_add(&[&g1[..], &zero[..]].concat()),
+            Err(Error::PointNotOnCurve)
+        );
+    }
+
+    #[test]
+    fn test_subgroup_checks() {
+        // (0, 2) is on y^2 = x^3 + 4 but not in the prime-order subgroup.
+        let mut p = [0_u8; 128];
+        p[127] = 2;
+        let other = hex::decode(G1_GENERATOR).unwrap();
+        assert!(g1_add(&[&p[..], &other[..]].concat()).is_ok());
+        assert_eq!(
+            g1_msm(&[&p[..], &scalar(1)].concat()),
+            Err(Error::PointNotInGroup)
+        );
+        let input = [&p[..], &hex::decode(G2_GENERATOR).unwrap()[..]].concat();
+        assert_eq!(pairing(&input), Err(Error::PointNotInGroup));
+    }
+
+    // The `_NU_` vectors from RFC 9380 give `P = clear_cofactor(map_to_curve(u))`,
+    // which is exactly what MAP_FP_TO_G1 and MAP_FP2_TO_G2 compute.
+    fn nu_vectors(json: &str) -> Vec<(Vec<u8>, Vec<u8>)> {
+        fn field<'a>(s: &'a str, name: &str) -> &'a str {
+            let key = format!("\"{}\": \"", name);
+            let start = s.find(&key).unwrap() + key.len();
+            &s[start..start + s[start..].find('"').unwrap()]
+        }
+        fn padded(v: &str) -> Vec<u8> {
+            v.split(',')
+                .flat_map(|c| {
+                    let mut out = vec![0_u8; 16];
+                    out.extend(hex::decode(c.trim_start_matches("0x")).unwrap());
+                    out
+                })
+                .collect()
+        }
+        json.split("\"P\": {")
+            .skip(1)
+            .map(|v| {
+                let u = &v[v.find("\"u\": [").unwrap()..];
+                let u = u.split('"').nth(3).unwrap();
+                let mut p = padded(field(v, "x"));
+                p.extend(padded(field(v, "y")));
+                (padded(u), p)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_map_to_curve_vectors() {
+        let g1 = include_str!(
+            "../blst/bindings/vectors/hash_to_curve/BLS12381G1_XMD_SHA-256_SSWU_NU_.json"
+        );
+        for (u, p) in nu_vectors(g1) {
+            assert_eq!(map_fp_to_g1(&u).unwrap(), p);
+        }
+        let g2 = include_str!(
+            "../blst/bindings/vectors/hash_to_curve/BLS12381G2_XMD_SHA-256_SSWU_NU_.json"
+        );
+        for (u, p) in nu_vectors(g2) {
+            assert_eq!(map_fp2_to_g2(&u).unwrap(), p);
+        }
+        assert_eq!(map_fp_to_g1(&[0; 63]), Err(Error::InvalidInputLength));
+        assert_eq!(map_fp2_to_g2(&[0; 64]), Err(Error::InvalidInputLength));
+    }
+
+    // Maps the go-ethereum error messages used in the `fail-*` files.
+    fn expected_error(message: &str) -> Option<Error> {
+        if message.contains("length") {
+            Some(Error::InvalidInputLength)
+        } else if message.contains("top bytes") || message.contains("modulus") {
+            Some(Error::BadEncoding)
+        } else if message.contains("not on curve") {
+            Some(Error::PointNotOnCurve)
+        } else if message.contains("subgroup") {
+            Some(Error::PointNotInGroup)
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    #[ignore = "the upstream EIP-2537 vectors are not vendored yet"]
+    fn test_eip2537_vectors() {
+        let dir = vectors::dir("eip2537");
+        let suites: [(&str, vectors::Precompile); 9] = [
+            ("add_G1_bls", g1_add),
+            ("mul_G1_bls", g1_msm),
+            ("msm_G1_bls", g1_msm),
+            ("add_G2_bls", g2_add),
+            ("mul_G2_bls", g2_msm),
+            ("msm_G2_bls", g2_msm),
+            ("pairing_check_bls", pairing),
+            ("map_fp_to_G1_bls", map_fp_to_g1),
+            ("map_fp2_to_G2_bls", map_fp2_to_g2),
+        ];
+        for (name, f) in suites {
+            vectors::run(&dir, name, f, expected_error);
+        }
+    }
+}
@@ -1,5 +1,21 @@
 #![cfg_attr(feature = "tstd", no_std)]
+
+extern crate alloc;
+
 pub mod bindings;
+pub mod bn254;
 
 mod rust;
-pub use rust::*;
\ No newline at end of file
+pub use rust::*;
+
+pub mod eip2537;
+pub mod msm;
+pub mod points;
+pub mod sha256;
+pub mod threshold;
+
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+#[cfg(test)]
+mod vectors;
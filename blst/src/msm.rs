@@ -0,0 +1,440 @@
+//! Multi-scalar multiplication over G1 and G2 using blst's Pippenger
+//! implementation.
+//!
+//! The serial path only needs `alloc` and therefore works the same under std
+//! and tstd. With the `parallel-std`/`parallel-tstd` features the work can be
+//! split across a caller-provided `threadpool::ThreadPool`.
+
+use crate::bindings::*;
+use crate::points::{G1Point, G2Point, Scalar};
+use crate::Error;
+
+use alloc::vec::Vec;
+use core::ptr;
+
+#[cfg(feature = "threadpool")]
+use alloc::sync::Arc;
+#[cfg(feature = "threadpool")]
+use core::cell::UnsafeCell;
+#[cfg(feature = "threadpool")]
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+const SCALAR_BITS: usize = 255;
+const SCALAR_BYTES: usize = 32;
+
+#[cfg(feature = "threadpool")]
+const MIN_CHUNK_SIZE: usize = 64;
+
+fn scalars_to_bytes(scalars: &[Scalar]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(scalars.len() * SCALAR_BYTES);
+    for s in scalars {
+        out.extend_from_slice(&s.to_bytes_le());
+    }
+    out
+}
+
+#[cfg(feature = "threadpool")]
+fn div_ceil(a: usize, b: usize) -> usize {
+    let q = a / b;
+    if q * b < a {
+        q + 1
+    } else {
+        q
+    }
+}
+
+// The partial sums of one `mult_parallel` call. Chunks are claimed through
+// `next` by the pool jobs and by the caller alike, so the caller never waits
+// for a chunk that nobody has started, even when the pool is busy or the call
+// is made from one of its own threads. Each slot is written by the single
+// claimant of its chunk and only read once `finished` covers every chunk,
+// so sharing it between threads is sound.
+#[cfg(feature = "threadpool")]
+struct Chunks<T> {
+    slots: Vec<UnsafeCell<T>>,
+    next: AtomicUsize,
+    finished: AtomicUsize,
+    failed: AtomicBool,
+}
+
+#[cfg(feature = "threadpool")]
+unsafe impl<T: Send> Sync for Chunks<T> {}
+
+#[cfg(feature = "threadpool")]
+impl<T: Copy + Default> Chunks<T> {
+    fn new(n: usize) -> Self {
+        Self {
+            slots: (0..n).map(|_| UnsafeCell::new(T::default())).collect(),
+            next: AtomicUsize::new(0),
+            finished: AtomicUsize::new(0),
+            failed: AtomicBool::new(false),
+        }
+    }
+
+    /// Evaluates unclaimed chunks until there are none left.
+    fn run<F: Fn(usize) -> T>(&self, f: F) {
+        loop {
+            let i = self.next.fetch_add(1, Ordering::Relaxed);
+            if i >= self.slots.len() {
+                return;
+            }
+            let mut finish = Finish {
+                chunks: self,
+                ok: false,
+            };
+            unsafe { *self.slots[i].get() = f(i) };
+            finish.ok = true;
+        }
+    }
+
+    /// Waits until every claimed chunk is finished. Only call this after
+    /// `run` has returned on the current thread, so that all chunks are
+    /// claimed. Returns `None` if one of them panicked.
+    fn wait(&self) -> Option<Vec<T>> {
+        while self.finished.load(Ordering::Acquire) < self.slots.len() {
+            relax();
+        }
+        if self.failed.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(
+            self.slots
+                .iter()
+                .map(|slot| unsafe { *slot.get() })
+                .collect(),
+        )
+    }
+}
+
+// Marks a claimed chunk as finished, and as failed if it unwinds.
+#[cfg(feature = "threadpool")]
+struct Finish<'a, T> {
+    chunks: &'a Chunks<T>,
+    ok: bool,
+}
+
+#[cfg(feature = "threadpool")]
+impl<T> Drop for Finish<'_, T> {
+    fn drop(&mut self) {
+        if !self.ok {
+            self.chunks.failed.store(true, Ordering::Relaxed);
+        }
+        self.chunks.finished.fetch_add(1, Ordering::Release);
+    }
+}
+
+#[cfg(all(feature = "threadpool", not(feature = "tstd")))]
+fn relax() {
+    std::thread::yield_now();
+}
+
+#[cfg(all(feature = "threadpool", feature = "tstd"))]
+fn relax() {
+    core::hint::spin_loop();
+}
+
+macro_rules! msm_impl {
+    (
+        $name:ident,
+        $group:ident,
+        $point:ty,
+        $affine:ty,
+        $to_affines:ident,
+        $scratch_sizeof:ident,
+        $pippenger:ident,
+        $mult:ident
+    ) => {
+        /// A batch of points converted to affine form once, so that several
+        /// multi-scalar multiplications over the same bases (e.g. a KZG setup)
+        /// don't repeat the normalisation.
+        #[derive(Debug, Clone, Default)]
+        pub struct $name {
+            points: Vec<$affine>,
+            // Pippenger can't take points at infinity, so they are dropped
+            // here; `indices` maps the remaining points back to their scalars.
+            indices: Option<Vec<usize>>,
+            len: usize,
+        }
+
+        impl $name {
+            pub fn new(points: &[$group]) -> Self {
+                // The batched affine conversion shares one inversion across
+                // all points, so a single identity would poison the batch.
+                let indices = if points.iter().any(|p| p.is_identity()) {
+                    Some(
+                        (0..points.len())
+                            .filter(|&i| !points[i].is_identity())
+                            .collect::<Vec<usize>>(),
+                    )
+                } else {
+                    None
+                };
+                let projective: Vec<$point> = match &indices {
+                    Some(kept) => kept.iter().map(|&i| points[i].0).collect(),
+                    None => points.iter().map(|p| p.0).collect(),
+                };
+
+                let mut affines = Vec::with_capacity(projective.len());
+                if !projective.is_empty() {
+                    let p: [*const $point; 2] = [&projective[0], ptr::null()];
+                    unsafe {
+                        $to_affines(affines.as_mut_ptr(), p.as_ptr(), projective.len());
+                        affines.set_len(projective.len());
+                    }
+                }
+                Self {
+                    points: affines,
+                    indices,
+                    len: points.len(),
+                }
+            }
+
+            pub fn len(&self) -> usize {
+                self.len
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.len == 0
+            }
+
+            /// Computes `sum(scalars[i] * points[i])`.
+            pub fn mult(&self, scalars: &[Scalar]) -> Result<$group, Error> {
+                Ok(Self::pippenger(&self.points, &self.scalar_bytes(scalars)?))
+            }
+
+            fn scalar_bytes(&self, scalars: &[Scalar]) -> Result<Vec<u8>, Error> {
+                if scalars.len() != self.len {
+                    return Err(Error::LengthMismatch);
+                }
+                Ok(match &self.indices {
+                    Some(indices) => {
+                        let kept: Vec<Scalar> = indices.iter().map(|&i| scalars[i]).collect();
+                        scalars_to_bytes(&kept)
+                    }
+                    None => scalars_to_bytes(scalars),
+                })
+            }
+
+            /// Same as `mult`, splitting the points into chunks that are
+            /// evaluated on `pool` and summed at the end. The calling thread
+            /// works on the chunks too and only waits for the ones already
+            /// running, so this may be called from a job of `pool` itself.
+            /// Fails with `Error::WorkerPanicked` if a chunk panicked.
+            #[cfg(feature = "threadpool")]
+            pub fn mult_parallel(
+                &self,
+                scalars: &[Scalar],
+                pool: &threadpool::ThreadPool,
+            ) -> Result<$group, Error> {
+                let scalars = self.scalar_bytes(scalars)?;
+                let npoints = self.points.len();
+                let ncpus = pool.max_count();
+                if ncpus < 2 || npoints < 2 * MIN_CHUNK_SIZE {
+                    return Ok(Self::pippenger(&self.points, &scalars));
+                }
+
+                let chunk = core::cmp::max(div_ceil(npoints, ncpus), MIN_CHUNK_SIZE);
+                let nchunks = div_ceil(npoints, chunk);
+                let points = Arc::new(self.points.clone());
+                let scalars = Arc::new(scalars);
+                let chunks: Arc<Chunks<$point>> = Arc::new(Chunks::new(nchunks));
+
+                for _ in 1..nchunks {
+                    let points = points.clone();
+                    let scalars = scalars.clone();
+                    let chunks = chunks.clone();
+                    pool.execute(move || {
+                        chunks.run(|i| Self::pippenger_chunk(&points, &scalars, chunk, i))
+                    });
+                }
+                chunks.run(|i| Self::pippenger_chunk(&points, &scalars, chunk, i));
+                let sums = chunks.wait().ok_or(Error::WorkerPanicked)?;
+
+                Ok(sums.into_iter().map($group).sum())
+            }
+
+            #[cfg(feature = "threadpool")]
+            fn pippenger_chunk(points: &[$affine], scalars: &[u8], chunk: usize, i: usize) -> $point {
+                let start = i * chunk;
+                let end = core::cmp::min(start + chunk, points.len());
+                Self::pippenger(
+                    &points[start..end],
+                    &scalars[start * SCALAR_BYTES..end * SCALAR_BYTES],
+                )
+                .0
+            }
+
+            fn pippenger(points: &[$affine], scalars: &[u8]) -> $group {
+                // blst reads one point ahead, so it needs at least two.
+                match points.len() {
+                    0 => return $group::identity(),
+                    1 => {
+                        let mut ret = <$point>::default();
+                        unsafe {
+                            $mult(
+                                &mut ret,
+                                &$group::from_affine(&points[0]).0,
+                                scalars.as_ptr(),
+                                SCALAR_BITS,
+                            )
+                        };
+                        return $group(ret);
+                    }
+                    _ => {}
+                }
+                let p: [*const $affine; 2] = [&points[0], ptr::null()];
+                let s: [*const u8; 2] = [&scalars[0], ptr::null()];
+                let mut ret = <$point>::default();
+                unsafe {
+                    let mut scratch: Vec<limb_t> = alloc::vec![0; $scratch_sizeof(points.len()) / 8];
+                    $pippenger(
+                        &mut ret,
+                        p.as_ptr(),
+                        points.len(),
+                        s.as_ptr(),
+                        SCALAR_BITS,
+                        scratch.as_mut_ptr(),
+                    );
+                }
+                $group(ret)
+            }
+        }
+
+        impl $group {
+            /// One-shot multi-scalar multiplication. Use the matching `*Affines`
+            /// type instead when the same bases are reused across calls.
+            pub fn msm(points: &[$group], scalars: &[Scalar]) -> Result<$group, Error> {
+                $name::new(points).mult(scalars)
+            }
+        }
+    };
+}
+
+msm_impl!(
+    G1Affines,
+    G1Point,
+    blst_p1,
+    blst_p1_affine,
+    blst_p1s_to_affine,
+    blst_p1s_mult_pippenger_scratch_sizeof,
+    blst_p1s_mult_pippenger,
+    blst_p1_mult
+);
+
+msm_impl!(
+    G2Affines,
+    G2Point,
+    blst_p2,
+    blst_p2_affine,
+    blst_p2s_to_affine,
+    blst_p2s_mult_pippenger_scratch_sizeof,
+    blst_p2s_mult_pippenger,
+    blst_p2_mult
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive<P>(points: &[P], scalars: &[Scalar]) -> P
+    where
+        P: Copy + core::ops::Mul<Scalar, Output = P> + core::iter::Sum,
+    {
+        points.iter().zip(scalars).map(|(p, s)| *p * *s).sum()
+    }
+
+    fn inputs(n: u64) -> (Vec<G1Point>, Vec<G2Point>, Vec<Scalar>) {
+        let scalars: Vec<Scalar> = (0..n)
+            .map(|i| Scalar::hash_to(&i.to_le_bytes(), b"MSM_TEST"))
+            .collect();
+        let g1 = (0..n)
+            .map(|i| G1Point::generator() * Scalar::from_u64(i * 7 + 1))
+            .collect();
+        let g2 = (0..n)
+            .map(|i| G2Point::generator() * Scalar::from_u64(i * 3 + 2))
+            .collect();
+        (g1, g2, scalars)
+    }
+
+    #[test]
+    fn test_msm_matches_naive() {
+        for n in [0, 1, 2, 33, 300] {
+            let (g1, g2, scalars) = inputs(n);
+            assert_eq!(G1Point::msm(&g1, &scalars).unwrap(), naive(&g1, &scalars));
+            assert_eq!(G2Point::msm(&g2, &scalars).unwrap(), naive(&g2, &scalars));
+        }
+    }
+
+    #[test]
+    fn test_msm_edge_cases() {
+        let (mut g1, _, mut scalars) = inputs(40);
+        g1[3] = G1Point::identity();
+        scalars[5] = Scalar::zero();
+        assert_eq!(G1Point::msm(&g1, &scalars).unwrap(), naive(&g1, &scalars));
+        assert_eq!(G1Point::msm(&g1, &scalars[1..]), Err(Error::LengthMismatch));
+        assert_eq!(
+            G1Point::msm(&[G1Point::identity(); 3], &scalars[..3]).unwrap(),
+            G1Point::identity()
+        );
+    }
+
+    #[cfg(feature = "threadpool")]
+    #[test]
+    fn test_msm_parallel() {
+        let pool = threadpool::ThreadPool::new(4);
+        let (g1, g2, scalars) = inputs(1000);
+        let bases = G1Affines::new(&g1);
+        assert_eq!(
+            bases.mult_parallel(&scalars, &pool).unwrap(),
+            bases.mult(&scalars).unwrap()
+        );
+        let bases = G2Affines::new(&g2);
+        assert_eq!(
+            bases.mult_parallel(&scalars, &pool).unwrap(),
+            bases.mult(&scalars).unwrap()
+        );
+    }
+
+    #[cfg(feature = "threadpool")]
+    #[test]
+    fn test_msm_parallel_inside_pool() {
+        // Every worker runs an outer job that calls `mult_parallel` on the
+        // same pool, so no worker is left to pick up the inner jobs.
+        let pool = threadpool::ThreadPool::new(2);
+        let (g1, _, scalars) = inputs(500);
+        let bases = Arc::new(G1Affines::new(&g1));
+        let scalars = Arc::new(scalars);
+        let expected = bases.mult(&scalars).unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        for _ in 0..pool.max_count() {
+            let (bases, scalars, inner, tx) =
+                (bases.clone(), scalars.clone(), pool.clone(), tx.clone());
+            pool.execute(move || tx.send(bases.mult_parallel(&scalars, &inner)).unwrap());
+        }
+        for _ in 0..pool.max_count() {
+            assert_eq!(rx.recv().unwrap().unwrap(), expected);
+        }
+    }
+
+    #[cfg(feature = "threadpool")]
+    #[test]
+    fn test_chunks_report_panics() {
+        let chunks = Chunks::<u64>::new(4);
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            chunks.run(|i| {
+                if i == 1 {
+                    panic!("chunk {}", i)
+                } else {
+                    i as u64
+                }
+            })
+        }));
+        assert!(panicked.is_err());
+        chunks.run(|i| i as u64);
+        assert_eq!(chunks.wait(), None);
+
+        let chunks = Chunks::<u64>::new(4);
+        chunks.run(|i| i as u64 * 2);
+        assert_eq!(chunks.wait(), Some(vec![0, 2, 4, 6]));
+    }
+}
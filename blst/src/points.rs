@@ -0,0 +1,789 @@
+//! Safe wrappers for BLS12-381 scalars, G1/G2 points and GT elements, for
+//! protocols that need raw curve operations rather than signatures.
+
+use crate::bindings::*;
+use crate::min_pk::{PublicKey, Signature};
+use crate::Error;
+
+use core::iter::Sum;
+use core::mem::MaybeUninit;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use zeroize::Zeroize;
+
+impl Default for blst_fp12 {
+    fn default() -> Self {
+        unsafe { *blst_fp12_one() }
+    }
+}
+
+impl Mul for blst_fp12 {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut out = MaybeUninit::<blst_fp12>::uninit();
+        unsafe {
+            blst_fp12_mul(out.as_mut_ptr(), &self, &other);
+            out.assume_init()
+        }
+    }
+}
+
+impl MulAssign for blst_fp12 {
+    fn mul_assign(&mut self, other: Self) {
+        unsafe { blst_fp12_mul(self, self, &other) }
+    }
+}
+
+impl blst_fp12 {
+    pub fn miller_loop(q: &blst_p2_affine, p: &blst_p1_affine) -> Self {
+        let mut out = MaybeUninit::<blst_fp12>::uninit();
+        unsafe {
+            blst_miller_loop(out.as_mut_ptr(), q, p);
+            out.assume_init()
+        }
+    }
+
+    pub fn final_exp(&self) -> Self {
+        let mut out = MaybeUninit::<blst_fp12>::uninit();
+        unsafe {
+            blst_final_exp(out.as_mut_ptr(), self);
+            out.assume_init()
+        }
+    }
+
+    pub fn in_group(&self) -> bool {
+        unsafe { blst_fp12_in_group(self) }
+    }
+
+    pub fn finalverify(a: &Self, b: &Self) -> bool {
+        unsafe { blst_fp12_finalverify(a, b) }
+    }
+
+    pub fn to_bendian(&self) -> [u8; 48 * 12] {
+        let mut out = MaybeUninit::<[u8; 48 * 12]>::uninit();
+        unsafe {
+            blst_bendian_from_fp12(out.as_mut_ptr() as *mut u8, self);
+            out.assume_init()
+        }
+    }
+}
+
+/// An element of the scalar field Fr, kept in Montgomery form.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Scalar(pub(crate) blst_fr);
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.l.zeroize();
+    }
+}
+
+impl Scalar {
+    pub const BYTES: usize = 32;
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn one() -> Self {
+        Self::from_u64(1)
+    }
+
+    pub fn from_u64(v: u64) -> Self {
+        let limbs = [v, 0, 0, 0];
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_from_uint64(&mut out, limbs.as_ptr()) };
+        Self(out)
+    }
+
+    /// Parses a canonical big-endian encoding, rejecting values `>= r`.
+    pub fn from_bytes_be(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::BYTES {
+            return Err(Error::BadEncoding);
+        }
+        let mut scalar = blst_scalar::default();
+        unsafe { blst_scalar_from_bendian(&mut scalar, bytes.as_ptr()) };
+        Self::from_scalar(&scalar)
+    }
+
+    /// Parses a canonical little-endian encoding, rejecting values `>= r`.
+    pub fn from_bytes_le(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::BYTES {
+            return Err(Error::BadEncoding);
+        }
+        let mut scalar = blst_scalar::default();
+        unsafe { blst_scalar_from_lendian(&mut scalar, bytes.as_ptr()) };
+        Self::from_scalar(&scalar)
+    }
+
+    /// Interprets `bytes` as a big-endian integer of any length and reduces it
+    /// modulo `r`.
+    pub fn from_bytes_be_reduced(bytes: &[u8]) -> Self {
+        let mut scalar = blst_scalar::default();
+        unsafe { blst_scalar_from_be_bytes(&mut scalar, bytes.as_ptr(), bytes.len()) };
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_from_scalar(&mut out, &scalar) };
+        Self(out)
+    }
+
+    /// Hashes `msg` to a scalar with `expand_message_xmd` as in the
+    /// hash-to-field step of RFC 9380.
+    pub fn hash_to(msg: &[u8], dst: &[u8]) -> Self {
+        let mut elem = [0_u8; 48];
+        unsafe {
+            blst_expand_message_xmd(
+                elem.as_mut_ptr(),
+                elem.len(),
+                msg.as_ptr(),
+                msg.len(),
+                dst.as_ptr(),
+                dst.len(),
+            )
+        };
+        Self::from_bytes_be_reduced(&elem)
+    }
+
+    fn from_scalar(scalar: &blst_scalar) -> Result<Self, Error> {
+        if !unsafe { blst_scalar_fr_check(scalar) } {
+            return Err(Error::BadScalar);
+        }
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_from_scalar(&mut out, scalar) };
+        Ok(Self(out))
+    }
+
+    pub(crate) fn to_scalar(self) -> blst_scalar {
+        let mut out = blst_scalar::default();
+        unsafe { blst_scalar_from_fr(&mut out, &self.0) };
+        out
+    }
+
+    pub fn to_bytes_be(self) -> [u8; 32] {
+        let mut out = [0_u8; 32];
+        unsafe { blst_bendian_from_scalar(out.as_mut_ptr(), &self.to_scalar()) };
+        out
+    }
+
+    pub fn to_bytes_le(self) -> [u8; 32] {
+        self.to_scalar().b
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == blst_fr::default()
+    }
+
+    /// Returns `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_inverse(&mut out, &self.0) };
+        Some(Self(out))
+    }
+
+    pub fn square(&self) -> Self {
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_sqr(&mut out, &self.0) };
+        Self(out)
+    }
+}
+
+impl Add for Scalar {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_add(&mut out, &self.0, &other.0) };
+        Self(out)
+    }
+}
+
+impl Sub for Scalar {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_sub(&mut out, &self.0, &other.0) };
+        Self(out)
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_mul(&mut out, &self.0, &other.0) };
+        Self(out)
+    }
+}
+
+impl Neg for Scalar {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let mut out = blst_fr::default();
+        unsafe { blst_fr_cneg(&mut out, &self.0, true) };
+        Self(out)
+    }
+}
+
+impl AddAssign for Scalar {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Scalar {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Scalar {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
+
+impl Sum for Scalar {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, x| acc + x)
+    }
+}
+
+macro_rules! point_impl {
+    (
+        $name:ident,
+        $point:ty,
+        $affine:ty,
+        $comp_size:expr,
+        $ser_size:expr,
+        $generator:ident,
+        $is_inf:ident,
+        $is_equal:ident,
+        $on_curve:ident,
+        $in_group:ident,
+        $add_or_double:ident,
+        $mult:ident,
+        $cneg:ident,
+        $from_affine:ident,
+        $to_affine:ident,
+        $compress:ident,
+        $serialize:ident,
+        $uncompress:ident,
+        $deserialize:ident,
+        $affine_in_group:ident,
+        $hash_to:ident,
+        $encode_to:ident
+    ) => {
+        /// A point in projective coordinates. Points built from bytes are
+        /// always checked to be on the curve and in the prime-order subgroup.
+        #[derive(Debug, Default, Clone, Copy)]
+        pub struct $name(pub(crate) $point);
+
+        impl $name {
+            pub const COMPRESSED_BYTES: usize = $comp_size;
+            pub const SERIALIZED_BYTES: usize = $ser_size;
+
+            pub fn generator() -> Self {
+                Self(unsafe { *$generator() })
+            }
+
+            /// The point at infinity.
+            pub fn identity() -> Self {
+                Self::default()
+            }
+
+            pub fn is_identity(&self) -> bool {
+                unsafe { $is_inf(&self.0) }
+            }
+
+            pub fn is_on_curve(&self) -> bool {
+                unsafe { $on_curve(&self.0) }
+            }
+
+            pub fn in_group(&self) -> bool {
+                unsafe { $in_group(&self.0) }
+            }
+
+            pub fn from_affine(p: &$affine) -> Self {
+                let mut out = <$point>::default();
+                unsafe { $from_affine(&mut out, p) };
+                Self(out)
+            }
+
+            pub fn to_affine(&self) -> $affine {
+                let mut out = <$affine>::default();
+                unsafe { $to_affine(&mut out, &self.0) };
+                out
+            }
+
+            /// Hashes `msg` to the curve with the random-oracle construction
+            /// (`hash_to_curve` from RFC 9380).
+            pub fn hash_to_curve(msg: &[u8], dst: &[u8]) -> Self {
+                Self::hash_to_curve_aug(msg, dst, &[])
+            }
+
+            /// `hash_to_curve` over `aug || msg`, as used by the message
+            /// augmentation ciphersuite.
+            pub fn hash_to_curve_aug(msg: &[u8], dst: &[u8], aug: &[u8]) -> Self {
+                let mut out = <$point>::default();
+                unsafe {
+                    $hash_to(
+                        &mut out,
+                        msg.as_ptr(),
+                        msg.len(),
+                        dst.as_ptr(),
+                        dst.len(),
+                        aug.as_ptr(),
+                        aug.len(),
+                    )
+                };
+                Self(out)
+            }
+
+            /// Maps `msg` to the curve with the cheaper non-uniform
+            /// construction (`encode_to_curve` from RFC 9380). The output is not
+            /// indistinguishable from random, so prefer `hash_to_curve` unless
+            /// the protocol says otherwise.
+            pub fn encode_to_curve(msg: &[u8], dst: &[u8]) -> Self {
+                Self::encode_to_curve_aug(msg, dst, &[])
+            }
+
+            pub fn encode_to_curve_aug(msg: &[u8], dst: &[u8], aug: &[u8]) -> Self {
+                let mut out = <$point>::default();
+                unsafe {
+                    $encode_to(
+                        &mut out,
+                        msg.as_ptr(),
+                        msg.len(),
+                        dst.as_ptr(),
+                        dst.len(),
+                        aug.as_ptr(),
+                        aug.len(),
+                    )
+                };
+                Self(out)
+            }
+
+            pub fn compress(&self) -> [u8; $comp_size] {
+                let mut out = [0_u8; $comp_size];
+                unsafe { $compress(out.as_mut_ptr(), &self.0) };
+                out
+            }
+
+            pub fn serialize(&self) -> [u8; $ser_size] {
+                let mut out = [0_u8; $ser_size];
+                unsafe { $serialize(out.as_mut_ptr(), &self.0) };
+                out
+            }
+
+            pub fn from_compressed(bytes: &[u8]) -> Result<Self, Error> {
+                if bytes.len() != $comp_size {
+                    return Err(Error::BadEncoding);
+                }
+                let mut p = <$affine>::default();
+                Error::check(unsafe { $uncompress(&mut p, bytes.as_ptr()) })?;
+                Self::from_checked_affine(&p)
+            }
+
+            pub fn from_uncompressed(bytes: &[u8]) -> Result<Self, Error> {
+                if bytes.len() != $ser_size {
+                    return Err(Error::BadEncoding);
+                }
+                let mut p = <$affine>::default();
+                Error::check(unsafe { $deserialize(&mut p, bytes.as_ptr()) })?;
+                Self::from_checked_affine(&p)
+            }
+
+            fn from_checked_affine(p: &$affine) -> Result<Self, Error> {
+                if !unsafe { $affine_in_group(p) } {
+                    return Err(Error::PointNotInGroup);
+                }
+                Ok(Self::from_affine(p))
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                unsafe { $is_equal(&self.0, &other.0) }
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl Add for $name {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                let mut out = <$point>::default();
+                unsafe { $add_or_double(&mut out, &self.0, &other.0) };
+                Self(out)
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            fn neg(mut self) -> Self {
+                unsafe { $cneg(&mut self.0, true) };
+                self
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                self + (-other)
+            }
+        }
+
+        impl Mul<Scalar> for $name {
+            type Output = Self;
+
+            fn mul(self, scalar: Scalar) -> Self {
+                let bytes = scalar.to_scalar();
+                let mut out = <$point>::default();
+                unsafe { $mult(&mut out, &self.0, bytes.b.as_ptr(), 255) };
+                Self(out)
+            }
+        }
+
+        impl Mul<$name> for Scalar {
+            type Output = $name;
+
+            fn mul(self, point: $name) -> $name {
+                point * self
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl MulAssign<Scalar> for $name {
+            fn mul_assign(&mut self, scalar: Scalar) {
+                *self = *self * scalar;
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::identity(), |acc, p| acc + p)
+            }
+        }
+
+        impl From<$affine> for $name {
+            fn from(p: $affine) -> Self {
+                Self::from_affine(&p)
+            }
+        }
+    };
+}
+
+point_impl!(
+    G1Point,
+    blst_p1,
+    blst_p1_affine,
+    48,
+    96,
+    blst_p1_generator,
+    blst_p1_is_inf,
+    blst_p1_is_equal,
+    blst_p1_on_curve,
+    blst_p1_in_g1,
+    blst_p1_add_or_double,
+    blst_p1_mult,
+    blst_p1_cneg,
+    blst_p1_from_affine,
+    blst_p1_to_affine,
+    blst_p1_compress,
+    blst_p1_serialize,
+    blst_p1_uncompress,
+    blst_p1_deserialize,
+    blst_p1_affine_in_g1,
+    blst_hash_to_g1,
+    blst_encode_to_g1
+);
+
+point_impl!(
+    G2Point,
+    blst_p2,
+    blst_p2_affine,
+    96,
+    192,
+    blst_p2_generator,
+    blst_p2_is_inf,
+    blst_p2_is_equal,
+    blst_p2_on_curve,
+    blst_p2_in_g2,
+    blst_p2_add_or_double,
+    blst_p2_mult,
+    blst_p2_cneg,
+    blst_p2_from_affine,
+    blst_p2_to_affine,
+    blst_p2_compress,
+    blst_p2_serialize,
+    blst_p2_uncompress,
+    blst_p2_deserialize,
+    blst_p2_affine_in_g2,
+    blst_hash_to_g2,
+    blst_encode_to_g2
+);
+
+impl From<PublicKey> for G1Point {
+    fn from(pk: PublicKey) -> Self {
+        Self::from_affine(&pk.0)
+    }
+}
+
+impl From<Signature> for G2Point {
+    fn from(sig: Signature) -> Self {
+        Self::from_affine(&sig.0)
+    }
+}
+
+/// An element of the target group GT, the order-`r` subgroup of Fp12.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gt(pub(crate) blst_fp12);
+
+impl Default for Gt {
+    fn default() -> Self {
+        Self::one()
+    }
+}
+
+impl Gt {
+    pub const BYTES: usize = 48 * 12;
+
+    pub fn one() -> Self {
+        Self(blst_fp12::default())
+    }
+
+    pub fn is_one(&self) -> bool {
+        unsafe { blst_fp12_is_one(&self.0) }
+    }
+
+    pub fn in_group(&self) -> bool {
+        self.0.in_group()
+    }
+
+    /// The optimal ate pairing `e(p, q)`.
+    pub fn pairing(p: &G1Point, q: &G2Point) -> Self {
+        Self::multi_pairing(&[(*p, *q)])
+    }
+
+    /// `prod e(p_i, q_i)`, sharing a single final exponentiation.
+    pub fn multi_pairing(pairs: &[(G1Point, G2Point)]) -> Self {
+        let mut acc = blst_fp12::default();
+        for (p, q) in pairs {
+            if p.is_identity() || q.is_identity() {
+                continue;
+            }
+            acc *= blst_fp12::miller_loop(&q.to_affine(), &p.to_affine());
+        }
+        Self(acc.final_exp())
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut out = self.0;
+        unsafe { blst_fp12_inverse(&mut out, &self.0) };
+        Self(out)
+    }
+
+    pub fn to_bytes(&self) -> [u8; 48 * 12] {
+        self.0.to_bendian()
+    }
+}
+
+impl Mul for Gt {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0)
+    }
+}
+
+impl MulAssign for Gt {
+    fn mul_assign(&mut self, other: Self) {
+        self.0 *= other.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scalar_field() {
+        let a = Scalar::from_u64(7);
+        let b = Scalar::from_u64(5);
+        assert_eq!(a + b, Scalar::from_u64(12));
+        assert_eq!(a - b, Scalar::from_u64(2));
+        assert_eq!(a * b, Scalar::from_u64(35));
+        assert_eq!(b - a + Scalar::from_u64(2), Scalar::zero());
+        assert_eq!(a * a.inverse().unwrap(), Scalar::one());
+        assert!(Scalar::zero().inverse().is_none());
+        assert_eq!(-a + a, Scalar::zero());
+
+        let bytes = a.to_bytes_be();
+        assert_eq!(bytes[31], 7);
+        assert_eq!(Scalar::from_bytes_be(&bytes).unwrap(), a);
+        assert_eq!(Scalar::from_bytes_le(&a.to_bytes_le()).unwrap(), a);
+        assert_eq!(Scalar::from_bytes_be(&[0xff; 32]), Err(Error::BadScalar));
+        assert_eq!(Scalar::from_bytes_be(&[0; 31]), Err(Error::BadEncoding));
+    }
+
+    #[test]
+    fn test_group_law() {
+        let g = G1Point::generator();
+        let two = Scalar::from_u64(2);
+        assert_eq!(g + g, g * two);
+        assert_eq!(g - g, G1Point::identity());
+        assert!((g - g).is_identity());
+        assert_eq!(two * g - g, g);
+        assert_eq!(
+            vec![g, g, g].into_iter().sum::<G1Point>(),
+            g * Scalar::from_u64(3)
+        );
+
+        let h = G2Point::generator();
+        assert_eq!(h + h + h, h * Scalar::from_u64(3));
+        assert_eq!(-h + h, G2Point::identity());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let p = G1Point::generator() * Scalar::from_u64(42);
+        assert_eq!(G1Point::from_compressed(&p.compress()).unwrap(), p);
+        assert_eq!(G1Point::from_uncompressed(&p.serialize()).unwrap(), p);
+        assert_eq!(
+            G1Point::from_compressed(&G1Point::identity().compress()).unwrap(),
+            G1Point::identity()
+        );
+
+        let q = G2Point::generator() * Scalar::from_u64(42);
+        assert_eq!(G2Point::from_compressed(&q.compress()).unwrap(), q);
+        assert_eq!(G2Point::from_uncompressed(&q.serialize()).unwrap(), q);
+
+        let mut bad = p.serialize();
+        bad[95] ^= 1;
+        assert_eq!(
+            G1Point::from_uncompressed(&bad),
+            Err(Error::PointNotOnCurve)
+        );
+        assert_eq!(G1Point::from_compressed(&[0; 47]), Err(Error::BadEncoding));
+    }
+
+    #[test]
+    fn test_pairing_bilinearity() {
+        let a = Scalar::from_u64(11);
+        let b = Scalar::from_u64(13);
+        let p = G1Point::generator();
+        let q = G2Point::generator();
+
+        let e = Gt::pairing(&p, &q);
+        assert!(!e.is_one());
+        assert!(e.in_group());
+        assert_eq!(
+            Gt::pairing(&(p * a), &(q * b)),
+            Gt::pairing(&(p * (a * b)), &q)
+        );
+        assert_eq!(Gt::pairing(&(p * a), &q), Gt::pairing(&p, &(q * a)));
+        assert!(Gt::multi_pairing(&[(p * a, q), (-p, q * a)]).is_one());
+        assert_eq!(e * e.inverse(), Gt::one());
+        assert!(Gt::pairing(&G1Point::identity(), &q).is_one());
+    }
+
+    // Minimal reader for the RFC 9380 vector files shipped with blst: yields
+    // `(msg, uncompressed P)` for every entry.
+    fn h2c_vectors(json: &str) -> Vec<(String, Vec<u8>)> {
+        fn field<'a>(s: &'a str, name: &str) -> &'a str {
+            let key = format!("\"{}\": \"", name);
+            let start = s.find(&key).unwrap() + key.len();
+            &s[start..start + s[start..].find('"').unwrap()]
+        }
+        // blst serialises Fp2 elements as `c1 || c0`, the vectors list `c0,c1`.
+        fn coord(v: &str) -> Vec<u8> {
+            v.split(',')
+                .rev()
+                .flat_map(|c| hex::decode(c.trim_start_matches("0x")).unwrap())
+                .collect()
+        }
+        json.split("\"P\": {")
+            .skip(1)
+            .map(|v| {
+                let mut p = coord(field(v, "x"));
+                p.extend(coord(field(v, "y")));
+                (field(v, "msg").to_string(), p)
+            })
+            .collect()
+    }
+
+    macro_rules! h2c_test {
+        ($name:ident, $point:ident, $method:ident, $file:expr) => {
+            #[test]
+            fn $name() {
+                let json = include_str!(concat!("../blst/bindings/vectors/hash_to_curve/", $file));
+                let dst = json.split("\"dst\": \"").nth(1).unwrap();
+                let dst = &dst[..dst.find('"').unwrap()];
+                let vectors = h2c_vectors(json);
+                assert_eq!(vectors.len(), 5);
+                for (msg, expected) in vectors {
+                    let p = $point::$method(msg.as_bytes(), dst.as_bytes());
+                    assert!(p.in_group());
+                    assert_eq!(&p.serialize()[..], &expected[..], "msg {:?}", msg);
+                }
+            }
+        };
+    }
+
+    h2c_test!(
+        test_hash_to_g1_vectors,
+        G1Point,
+        hash_to_curve,
+        "BLS12381G1_XMD_SHA-256_SSWU_RO_.json"
+    );
+    h2c_test!(
+        test_encode_to_g1_vectors,
+        G1Point,
+        encode_to_curve,
+        "BLS12381G1_XMD_SHA-256_SSWU_NU_.json"
+    );
+    h2c_test!(
+        test_hash_to_g2_vectors,
+        G2Point,
+        hash_to_curve,
+        "BLS12381G2_XMD_SHA-256_SSWU_RO_.json"
+    );
+    h2c_test!(
+        test_encode_to_g2_vectors,
+        G2Point,
+        encode_to_curve,
+        "BLS12381G2_XMD_SHA-256_SSWU_NU_.json"
+    );
+
+    #[test]
+    fn test_hash_to_curve_aug() {
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        assert_eq!(
+            G1Point::hash_to_curve_aug(b"def", dst, b"abc"),
+            G1Point::hash_to_curve(b"abcdef", dst)
+        );
+        assert_eq!(
+            G2Point::encode_to_curve_aug(b"def", dst, b"abc"),
+            G2Point::encode_to_curve(b"abcdef", dst)
+        );
+    }
+}
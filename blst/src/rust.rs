@@ -1,196 +1,761 @@
 use super::bindings::*;
 
-use core::ptr::null_mut;
+use core::fmt;
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
-pub struct PublicKey(blst_p1_affine);
-
-#[derive(Default, Clone, PartialEq, Eq)]
-pub struct SecretKey(blst_scalar);
-
-impl core::fmt::Debug for SecretKey {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "0x{}...{}", hex::encode(&self.to_bytes()[..2]), hex::encode(&self.to_bytes()[30..]))
-    }
-}
-
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
-pub struct Signature(blst_p2_affine);
-
-const PUBLIC_KEY_LENGTH: usize = 48;
-const SECRET_KEY_LENGTH: usize = 32;
-// const SIGNATURE_LENGTH: usize = 96;
-const BLST_FP_BYTES: usize = 384 / 8;
-const BLST_P1_COMPRESS_BYTES: usize = BLST_FP_BYTES;
-const BLST_P2_COMPRESS_BYTES: usize = BLST_FP_BYTES * 2;
-const BLS_SIG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
-
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum Error {
+    /// The secret key is zero or not below the group order.
     DeserializeSecretKey,
+    /// The public key is not valid hex.
     InvalidPubkey,
+    /// Wrong number of bytes for a public key.
     InvalidPubkeyLength,
+    /// Wrong number of bytes for a secret key.
     InvalidSecretKeyLength,
+    /// The signature is not valid hex or is the point at infinity.
     InvalidSignature,
+    /// Wrong number of bytes for a signature.
     InvalidSignatureLength,
-    UncompressPubkey,
-    UncompressSignature,
+    /// Key generation needs at least 32 bytes of input keying material.
+    InvalidIkmLength,
+    /// The threshold is zero or there are not enough shares to meet it.
+    InvalidThreshold,
+    /// A share index is zero.
+    InvalidShareIndex,
+    /// The same share index was given more than once.
+    DuplicateShareIndex,
+    /// The bytes are not a valid point or scalar encoding.
+    BadEncoding,
+    /// The decoded coordinates do not satisfy the curve equation.
+    PointNotOnCurve,
+    /// The point is not in the prime-order subgroup.
+    PointNotInGroup,
+    /// A pairing aggregate mixed signatures from G1 and G2.
+    AggregateTypeMismatch,
+    /// Signature verification failed.
+    VerifyFail,
+    /// The public key is the point at infinity.
+    PkIsInfinity,
+    /// The scalar is not a canonical field element.
+    BadScalar,
+    /// Paired arguments have different lengths.
+    LengthMismatch,
+    /// The requested output is longer than the function can produce.
+    InvalidOutputLength,
+    /// The input has the wrong length for the operation.
+    InvalidInputLength,
+    /// A job of a parallel computation panicked.
+    WorkerPanicked,
 }
 
-pub fn sign(sk: &SecretKey, msg: &[u8]) -> Signature {
-    let mut sig = Signature::default();
-    sig.sign(sk, msg, BLS_SIG);
-    sig
+impl Error {
+    /// Maps a raw blst return code, treating `BLST_SUCCESS` as `Ok`.
+    pub fn check(err: BLST_ERROR) -> Result<(), Error> {
+        err.into()
+    }
 }
 
-pub fn keygen_v3(ikm: [u8; 32]) -> SecretKey {
-    let mut seckey = SecretKey::default();
-    let info = &[];
-    unsafe {
-        blst_keygen_v3(
-            &mut seckey.0 as *mut _,
-            ikm.as_ptr(),
-            ikm.len(),
-            info.as_ptr(),
-            info.len(),
-        )
+/// Lets callers write `Result::from(code)?`; `BLST_SUCCESS` maps to `Ok`.
+impl From<BLST_ERROR> for Result<(), Error> {
+    fn from(err: BLST_ERROR) -> Self {
+        Err(match err {
+            BLST_ERROR::BLST_SUCCESS => return Ok(()),
+            BLST_ERROR::BLST_BAD_ENCODING => Error::BadEncoding,
+            BLST_ERROR::BLST_POINT_NOT_ON_CURVE => Error::PointNotOnCurve,
+            BLST_ERROR::BLST_POINT_NOT_IN_GROUP => Error::PointNotInGroup,
+            BLST_ERROR::BLST_AGGR_TYPE_MISMATCH => Error::AggregateTypeMismatch,
+            BLST_ERROR::BLST_VERIFY_FAIL => Error::VerifyFail,
+            BLST_ERROR::BLST_PK_IS_INFINITY => Error::PkIsInfinity,
+            BLST_ERROR::BLST_BAD_SCALAR => Error::BadScalar,
+        })
     }
-    seckey
 }
 
-impl Signature {
-    fn raw(&self) -> *const blst_p2_affine {
-        return &self.0 as *const blst_p2_affine;
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Error::DeserializeSecretKey => "invalid secret key",
+            Error::InvalidPubkey => "invalid public key",
+            Error::InvalidPubkeyLength => "invalid public key length",
+            Error::InvalidSecretKeyLength => "invalid secret key length",
+            Error::InvalidSignature => "invalid signature",
+            Error::InvalidSignatureLength => "invalid signature length",
+            Error::InvalidIkmLength => "input keying material shorter than 32 bytes",
+            Error::InvalidThreshold => "invalid threshold",
+            Error::InvalidShareIndex => "share index must be non-zero",
+            Error::DuplicateShareIndex => "duplicate share index",
+            Error::BadEncoding => "bad point or scalar encoding",
+            Error::PointNotOnCurve => "point is not on the curve",
+            Error::PointNotInGroup => "point is not in the prime-order subgroup",
+            Error::AggregateTypeMismatch => "aggregate type mismatch",
+            Error::VerifyFail => "signature verification failed",
+            Error::PkIsInfinity => "public key is the point at infinity",
+            Error::BadScalar => "scalar is not a canonical field element",
+            Error::LengthMismatch => "paired arguments have different lengths",
+            Error::InvalidOutputLength => "requested output length is too long",
+            Error::InvalidInputLength => "invalid input length",
+            Error::WorkerPanicked => "a parallel worker panicked",
+        })
     }
+}
 
-    fn raw_mut(&mut self) -> *mut blst_p2_affine {
-        &mut self.0 as *mut blst_p2_affine
-    }
+#[cfg(not(feature = "tstd"))]
+impl std::error::Error for Error {}
 
-    fn hash_to_g2(msg: &[u8], dst: &[u8]) -> blst_p2 {
-        let mut q = blst_p2::default();
-        let aug = [0_u8; 0];
-        unsafe {
-            blst_hash_to_g2(
-                &mut q as *mut blst_p2,
-                msg.as_ptr(),
-                msg.len(),
-                dst.as_ptr(),
-                dst.len(),
-                aug.as_ptr(),
-                aug.len(),
-            );
-        }
-        q
-    }
+macro_rules! sig_variant_impl {
+    (
+        $pk_aff:ty,
+        $sig_aff:ty,
+        $sig_proj:ty,
+        $sk_to_pk:ident,
+        $hash_to:ident,
+        $sign:ident,
+        $verify:ident,
+        $pk_is_inf:ident,
+        $pk_in_group:ident,
+        $pk_comp:ident,
+        $pk_uncomp:ident,
+        $sig_is_inf:ident,
+        $sig_in_group:ident,
+        $sig_comp:ident,
+        $sig_uncomp:ident,
+        $pk_comp_size:expr,
+        $sig_comp_size:expr,
+        $sig_group:expr
+    ) => {
+        use crate::bindings::*;
+        use crate::Error;
+
+        use core::ptr::null_mut;
+        use zeroize::Zeroize;
+
+        #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+        pub struct PublicKey(pub(crate) $pk_aff);
+
+        /// The scalar is wiped when the key goes out of scope. `Clone` and `Default`
+        /// are intentionally not implemented so that no implicit copies are made.
+        #[derive(Zeroize)]
+        #[zeroize(drop)]
+        pub struct SecretKey(pub(crate) blst_scalar);
 
-    pub fn sign(&mut self, sk: &SecretKey, msg: &[u8], dst: &[u8]) {
-        unsafe {
-            let hash = Self::hash_to_g2(msg, dst);
-            blst_sign_pk2_in_g1(
-                null_mut(),
-                self.raw_mut(),
-                &hash as *const blst_p2,
-                sk.raw(),
-            );
+        impl core::fmt::Debug for SecretKey {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let mut bytes = self.to_bytes();
+                let result = write!(f, "0x{}...{}", hex::encode(&bytes[..2]), hex::encode(&bytes[30..]));
+                bytes.zeroize();
+                result
+            }
         }
-    }
 
-    pub fn compress(&self) -> [u8; BLST_P2_COMPRESS_BYTES] {
-        let mut out = [0_u8; BLST_P2_COMPRESS_BYTES];
-        unsafe { blst_p2_affine_compress(out.as_mut_ptr(), self.raw()) }
-        out
-    }
-}
+        impl PartialEq for SecretKey {
+            fn eq(&self, other: &Self) -> bool {
+                self.ct_eq(other)
+            }
+        }
 
-impl PublicKey {
-    fn raw(&self) -> *const blst_p1_affine {
-        return &self.0 as *const blst_p1_affine;
-    }
+        impl Eq for SecretKey {}
 
-    fn raw_mut(&mut self) -> *mut blst_p1_affine {
-        &mut self.0 as *mut blst_p1_affine
-    }
+        #[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+        pub struct Signature(pub(crate) $sig_aff);
 
-    pub fn validate(&self) -> bool {
-        unsafe { !blst_p1_affine_is_inf(self.raw()) && blst_p1_affine_in_g1(self.raw()) }
-    }
+        const PUBLIC_KEY_LENGTH: usize = $pk_comp_size;
+        const SECRET_KEY_LENGTH: usize = 32;
+        const SIGNATURE_LENGTH: usize = $sig_comp_size;
+        const BLS_SIG: &[u8] = concat!("BLS_SIG_BLS12381", $sig_group, "_XMD:SHA-256_SSWU_RO_POP_").as_bytes();
+        const BLS_SIG_BASIC: &[u8] = concat!("BLS_SIG_BLS12381", $sig_group, "_XMD:SHA-256_SSWU_RO_NUL_").as_bytes();
+        const BLS_SIG_AUG: &[u8] = concat!("BLS_SIG_BLS12381", $sig_group, "_XMD:SHA-256_SSWU_RO_AUG_").as_bytes();
+        const BLS_POP: &[u8] = concat!("BLS_POP_BLS12381", $sig_group, "_XMD:SHA-256_SSWU_RO_POP_").as_bytes();
 
-    pub fn from_hex(hex_str: &[u8]) -> Result<Self, Error> {
-        match hex::decode(hex_str) {
-            Ok(data) => Self::from_bytes(&data),
-            Err(_) => Err(Error::InvalidPubkey),
+        /// The signature schemes from draft-irtf-cfrg-bls-signature, section 4.2.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Ciphersuite {
+            /// `_NUL_`: messages must be distinct when signatures are aggregated.
+            Basic,
+            /// `_AUG_`: the signer's compressed public key is prepended to the message.
+            MessageAugmentation,
+            /// `_POP_`: rogue-key protection relies on a separate proof of possession.
+            ProofOfPossession,
         }
-    }
 
-    pub fn compress(&self) -> [u8; BLST_P1_COMPRESS_BYTES] {
-        let mut out = [0_u8; BLST_P1_COMPRESS_BYTES];
-        unsafe { blst_p1_affine_compress(out.as_mut_ptr(), self.raw()) };
-        out
-    }
+        impl Default for Ciphersuite {
+            fn default() -> Self {
+                Ciphersuite::ProofOfPossession
+            }
+        }
 
-    pub fn from_bytes(pk_bytes: &[u8]) -> Result<Self, Error> {
-        if pk_bytes.len() != PUBLIC_KEY_LENGTH {
-            return Err(Error::InvalidPubkeyLength);
+        impl Ciphersuite {
+            pub fn dst(&self) -> &'static [u8] {
+                match self {
+                    Ciphersuite::Basic => BLS_SIG_BASIC,
+                    Ciphersuite::MessageAugmentation => BLS_SIG_AUG,
+                    Ciphersuite::ProofOfPossession => BLS_SIG,
+                }
+            }
+        }
+
+        pub fn sign(sk: &SecretKey, msg: &[u8]) -> Signature {
+            sign_with(sk, msg, Ciphersuite::ProofOfPossession)
         }
-        let mut pk = Self::default();
-        unsafe {
-            let err = blst_p1_uncompress(&mut pk.0 as *mut blst_p1_affine, pk_bytes.as_ptr());
-            if err != BLST_ERROR::BLST_SUCCESS {
-                return Err(Error::UncompressPubkey);
+
+        pub fn sign_with(sk: &SecretKey, msg: &[u8], suite: Ciphersuite) -> Signature {
+            let mut sig = Signature::default();
+            match suite {
+                Ciphersuite::MessageAugmentation => {
+                    let aug = sk.public().compress();
+                    sig.sign_aug(sk, msg, suite.dst(), &aug);
+                }
+                _ => sig.sign(sk, msg, suite.dst()),
             }
+            sig
         }
-        if !pk.validate() {
-            return Err(Error::InvalidPubkey);
+
+        pub fn verify(pk: &PublicKey, msg: &[u8], sig: &Signature) -> bool {
+            verify_with(pk, msg, sig, Ciphersuite::ProofOfPossession)
         }
-        Ok(pk)
-    }
-}
 
-impl SecretKey {
-    fn raw(&self) -> *const blst_scalar {
-        &self.0 as *const blst_scalar
-    }
+        pub fn verify_with(pk: &PublicKey, msg: &[u8], sig: &Signature, suite: Ciphersuite) -> bool {
+            match suite {
+                Ciphersuite::MessageAugmentation => sig.verify_aug(pk, msg, suite.dst(), &pk.compress()),
+                _ => sig.verify(pk, msg, suite.dst()),
+            }
+        }
 
-    pub fn raw_mut(&mut self) -> *mut blst_scalar {
-        &mut self.0 as *mut blst_scalar
-    }
+        /// KeyGen from draft-irtf-cfrg-bls-signature-03. The local copy of
+        /// the input keying material is wiped once the key has been derived.
+        pub fn keygen_v3(mut ikm: [u8; 32]) -> SecretKey {
+            let mut seckey = SecretKey::zero();
+            let info = &[];
+            unsafe {
+                blst_keygen_v3(
+                    &mut seckey.0 as *mut _,
+                    ikm.as_ptr(),
+                    ikm.len(),
+                    info.as_ptr(),
+                    info.len(),
+                )
+            }
+            ikm.zeroize();
+            seckey
+        }
 
-    pub fn public(&self) -> PublicKey {
-        let mut pk = PublicKey::default();
-        unsafe { blst_sk_to_pk2_in_g1(null_mut(), pk.raw_mut(), self.raw()) };
-        pk
-    }
+        /// KeyGen from draft-irtf-cfrg-bls-signature-04: `salt` is hashed
+        /// before first use and rehashed until a non-zero key comes out. With
+        /// `KEYGEN_SALT` and an empty `info` this is the EIP-2333 master key
+        /// derivation.
+        pub fn keygen_v4_5(ikm: &[u8], salt: &[u8], info: &[u8]) -> Result<SecretKey, Error> {
+            keygen_with(blst_keygen_v4_5, ikm, salt, info)
+        }
 
-    pub fn from_bytes(sk_bytes: &[u8]) -> Result<Self, Error> {
-        if sk_bytes.len() != SECRET_KEY_LENGTH {
-            return Err(Error::InvalidSecretKeyLength);
+        /// KeyGen from draft-irtf-cfrg-bls-signature-05: `salt` is used as is
+        /// for the first attempt.
+        pub fn keygen_v5(ikm: &[u8], salt: &[u8], info: &[u8]) -> Result<SecretKey, Error> {
+            keygen_with(blst_keygen_v5, ikm, salt, info)
         }
-        let mut sk = Self::default();
-        unsafe {
-            blst_scalar_from_bendian(&mut sk.0 as *mut blst_scalar, sk_bytes.as_ptr());
+
+        fn keygen_with(
+            keygen: unsafe extern "C" fn(*mut blst_scalar, *const u8, usize, *const u8, usize, *const u8, usize),
+            ikm: &[u8],
+            salt: &[u8],
+            info: &[u8],
+        ) -> Result<SecretKey, Error> {
+            if ikm.len() < 32 {
+                return Err(Error::InvalidIkmLength);
+            }
+            let mut seckey = SecretKey::zero();
+            unsafe {
+                keygen(
+                    &mut seckey.0,
+                    ikm.as_ptr(),
+                    ikm.len(),
+                    salt.as_ptr(),
+                    salt.len(),
+                    info.as_ptr(),
+                    info.len(),
+                )
+            }
+            Ok(seckey)
         }
-        if sk.is_empty() {
-            return Err(Error::InvalidSecretKeyLength);
+
+        impl Signature {
+            fn raw(&self) -> *const $sig_aff {
+                return &self.0 as *const $sig_aff;
+            }
+
+            fn raw_mut(&mut self) -> *mut $sig_aff {
+                &mut self.0 as *mut $sig_aff
+            }
+
+            fn hash_to_curve(msg: &[u8], dst: &[u8], aug: &[u8]) -> $sig_proj {
+                let mut q = <$sig_proj>::default();
+                unsafe {
+                    $hash_to(
+                        &mut q as *mut $sig_proj,
+                        msg.as_ptr(),
+                        msg.len(),
+                        dst.as_ptr(),
+                        dst.len(),
+                        aug.as_ptr(),
+                        aug.len(),
+                    );
+                }
+                q
+            }
+
+            pub fn sign(&mut self, sk: &SecretKey, msg: &[u8], dst: &[u8]) {
+                self.sign_aug(sk, msg, dst, &[])
+            }
+
+            pub fn sign_aug(&mut self, sk: &SecretKey, msg: &[u8], dst: &[u8], aug: &[u8]) {
+                unsafe {
+                    let hash = Self::hash_to_curve(msg, dst, aug);
+                    $sign(
+                        null_mut(),
+                        self.raw_mut(),
+                        &hash as *const $sig_proj,
+                        sk.raw(),
+                    );
+                }
+            }
+
+            pub fn verify(&self, pk: &PublicKey, msg: &[u8], dst: &[u8]) -> bool {
+                self.verify_aug(pk, msg, dst, &[])
+            }
+
+            pub fn verify_aug(&self, pk: &PublicKey, msg: &[u8], dst: &[u8], aug: &[u8]) -> bool {
+                if !self.validate() || !pk.validate() {
+                    return false;
+                }
+                let err = unsafe {
+                    $verify(
+                        pk.raw(),
+                        self.raw(),
+                        true,
+                        msg.as_ptr(),
+                        msg.len(),
+                        dst.as_ptr(),
+                        dst.len(),
+                        aug.as_ptr(),
+                        aug.len(),
+                    )
+                };
+                err == BLST_ERROR::BLST_SUCCESS
+            }
+
+            pub fn validate(&self) -> bool {
+                unsafe { !$sig_is_inf(self.raw()) && $sig_in_group(self.raw()) }
+            }
+
+            pub fn compress(&self) -> [u8; SIGNATURE_LENGTH] {
+                let mut out = [0_u8; SIGNATURE_LENGTH];
+                unsafe { $sig_comp(out.as_mut_ptr(), self.raw()) }
+                out
+            }
+
+            pub fn from_hex(hex_str: &[u8]) -> Result<Self, Error> {
+                match hex::decode(hex_str) {
+                    Ok(data) => Self::from_bytes(&data),
+                    Err(_) => Err(Error::InvalidSignature),
+                }
+            }
+
+            pub fn from_bytes(sig_bytes: &[u8]) -> Result<Self, Error> {
+                if sig_bytes.len() != SIGNATURE_LENGTH {
+                    return Err(Error::InvalidSignatureLength);
+                }
+                let mut sig = Self::default();
+                Error::check(unsafe { $sig_uncomp(sig.raw_mut(), sig_bytes.as_ptr()) })?;
+                if unsafe { $sig_is_inf(sig.raw()) } {
+                    return Err(Error::InvalidSignature);
+                }
+                if !unsafe { $sig_in_group(sig.raw()) } {
+                    return Err(Error::PointNotInGroup);
+                }
+                Ok(sig)
+            }
         }
-        Ok(sk)
-    }
 
-    pub fn is_empty(&self) -> bool {
-        self == &Self::default()
-    }
+        impl PublicKey {
+            fn raw(&self) -> *const $pk_aff {
+                return &self.0 as *const $pk_aff;
+            }
+
+            fn raw_mut(&mut self) -> *mut $pk_aff {
+                &mut self.0 as *mut $pk_aff
+            }
 
-    pub fn to_bytes(&self) -> [u8; SECRET_KEY_LENGTH] {
-        let mut bytes = [0_u8; SECRET_KEY_LENGTH];
-        unsafe {
-            blst_bendian_from_scalar(bytes.as_mut_ptr(), self.raw());
+            pub fn validate(&self) -> bool {
+                unsafe { !$pk_is_inf(self.raw()) && $pk_in_group(self.raw()) }
+            }
+
+            pub fn from_hex(hex_str: &[u8]) -> Result<Self, Error> {
+                match hex::decode(hex_str) {
+                    Ok(data) => Self::from_bytes(&data),
+                    Err(_) => Err(Error::InvalidPubkey),
+                }
+            }
+
+            pub fn compress(&self) -> [u8; PUBLIC_KEY_LENGTH] {
+                let mut out = [0_u8; PUBLIC_KEY_LENGTH];
+                unsafe { $pk_comp(out.as_mut_ptr(), self.raw()) };
+                out
+            }
+
+            pub fn from_bytes(pk_bytes: &[u8]) -> Result<Self, Error> {
+                if pk_bytes.len() != PUBLIC_KEY_LENGTH {
+                    return Err(Error::InvalidPubkeyLength);
+                }
+                let mut pk = Self::default();
+                Error::check(unsafe { $pk_uncomp(pk.raw_mut(), pk_bytes.as_ptr()) })?;
+                if unsafe { $pk_is_inf(pk.raw()) } {
+                    return Err(Error::PkIsInfinity);
+                }
+                if !unsafe { $pk_in_group(pk.raw()) } {
+                    return Err(Error::PointNotInGroup);
+                }
+                Ok(pk)
+            }
+
+            /// Checks a proof of possession produced by [`SecretKey::pop_prove`].
+            pub fn pop_verify(&self, proof: &Signature) -> bool {
+                proof.verify(self, &self.compress(), BLS_POP)
+            }
         }
-        bytes
-    }
+
+        impl SecretKey {
+            fn zero() -> Self {
+                Self(blst_scalar::default())
+            }
+
+            fn raw(&self) -> *const blst_scalar {
+                &self.0 as *const blst_scalar
+            }
+
+            pub fn raw_mut(&mut self) -> *mut blst_scalar {
+                &mut self.0 as *mut blst_scalar
+            }
+
+            pub fn public(&self) -> PublicKey {
+                let mut pk = PublicKey::default();
+                unsafe { $sk_to_pk(null_mut(), pk.raw_mut(), self.raw()) };
+                pk
+            }
+
+            /// Signs the compressed public key under the `BLS_POP_` domain, proving
+            /// knowledge of the secret key behind it.
+            pub fn pop_prove(&self) -> Signature {
+                let mut sig = Signature::default();
+                sig.sign(self, &self.public().compress(), BLS_POP);
+                sig
+            }
+
+            pub fn from_bytes(sk_bytes: &[u8]) -> Result<Self, Error> {
+                if sk_bytes.len() != SECRET_KEY_LENGTH {
+                    return Err(Error::InvalidSecretKeyLength);
+                }
+                let mut sk = Self::zero();
+                unsafe {
+                    blst_scalar_from_bendian(&mut sk.0 as *mut blst_scalar, sk_bytes.as_ptr());
+                }
+                if !unsafe { blst_sk_check(&sk.0) } {
+                    return Err(Error::DeserializeSecretKey);
+                }
+                Ok(sk)
+            }
+
+            pub fn is_empty(&self) -> bool {
+                let mut acc = 0_u8;
+                for b in self.0.b.iter() {
+                    acc |= b;
+                }
+                unsafe { core::ptr::read_volatile(&acc) == 0 }
+            }
+
+            /// Compares two secret keys without branching on their contents.
+            pub fn ct_eq(&self, other: &Self) -> bool {
+                let mut acc = 0_u8;
+                for (a, b) in self.0.b.iter().zip(other.0.b.iter()) {
+                    acc |= a ^ b;
+                }
+                unsafe { core::ptr::read_volatile(&acc) == 0 }
+            }
+
+            pub fn to_bytes(&self) -> [u8; SECRET_KEY_LENGTH] {
+                let mut bytes = [0_u8; SECRET_KEY_LENGTH];
+                unsafe {
+                    blst_bendian_from_scalar(bytes.as_mut_ptr(), self.raw());
+                }
+                bytes
+            }
+        }
+    };
 }
 
+/// Public keys in G1 (48 bytes) and signatures in G2 (96 bytes), as used by
+/// the Ethereum consensus layer.
+pub mod min_pk {
+    sig_variant_impl!(
+        blst_p1_affine,
+        blst_p2_affine,
+        blst_p2,
+        blst_sk_to_pk2_in_g1,
+        blst_hash_to_g2,
+        blst_sign_pk2_in_g1,
+        blst_core_verify_pk_in_g1,
+        blst_p1_affine_is_inf,
+        blst_p1_affine_in_g1,
+        blst_p1_affine_compress,
+        blst_p1_uncompress,
+        blst_p2_affine_is_inf,
+        blst_p2_affine_in_g2,
+        blst_p2_affine_compress,
+        blst_p2_uncompress,
+        48,
+        96,
+        "G2"
+    );
+}
+
+/// Public keys in G2 (96 bytes) and signatures in G1 (48 bytes).
+pub mod min_sig {
+    sig_variant_impl!(
+        blst_p2_affine,
+        blst_p1_affine,
+        blst_p1,
+        blst_sk_to_pk2_in_g2,
+        blst_hash_to_g1,
+        blst_sign_pk2_in_g2,
+        blst_core_verify_pk_in_g2,
+        blst_p2_affine_is_inf,
+        blst_p2_affine_in_g2,
+        blst_p2_affine_compress,
+        blst_p2_uncompress,
+        blst_p1_affine_is_inf,
+        blst_p1_affine_in_g1,
+        blst_p1_affine_compress,
+        blst_p1_uncompress,
+        96,
+        48,
+        "G1"
+    );
+}
+
+pub use min_pk::*;
+
+/// The default KeyGen salt from draft-irtf-cfrg-bls-signature.
+pub const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+
 pub fn sha256_sum(data: &[u8]) -> [u8; 32] {
     let mut out = [0_u8; 32];
     unsafe { blst_sha256(out.as_mut_ptr(), data.as_ptr(), data.len()) }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            Error::check(BLST_ERROR::BLST_POINT_NOT_IN_GROUP),
+            Err(Error::PointNotInGroup)
+        );
+        assert_eq!(Error::check(BLST_ERROR::BLST_SUCCESS), Ok(()));
+        assert_eq!(Result::from(BLST_ERROR::BLST_BAD_SCALAR), Err(Error::BadScalar));
+        assert_eq!(Result::<(), Error>::from(BLST_ERROR::BLST_SUCCESS), Ok(()));
+
+        let mut inf = [0_u8; 48];
+        inf[0] = 0xc0;
+        assert_eq!(PublicKey::from_bytes(&inf), Err(Error::PkIsInfinity));
+        assert_eq!(PublicKey::from_bytes(&[0; 48]), Err(Error::BadEncoding));
+        assert_eq!(
+            PublicKey::from_bytes(&[0; 47]),
+            Err(Error::InvalidPubkeyLength)
+        );
+        assert_eq!(
+            SecretKey::from_bytes(&[0; 32]).unwrap_err(),
+            Error::DeserializeSecretKey
+        );
+        assert_eq!(
+            SecretKey::from_bytes(&[0xff; 32]).unwrap_err(),
+            Error::DeserializeSecretKey
+        );
+        assert_eq!(
+            Error::PointNotOnCurve.to_string(),
+            "point is not on the curve"
+        );
+    }
+
+    // Checks that only depend on the API shared by `min_pk` and `min_sig`.
+    macro_rules! variant_tests {
+        ($variant:ident) => {
+            mod $variant {
+                use crate::$variant::*;
+
+                fn keys() -> (SecretKey, SecretKey) {
+                    (keygen_v3([1_u8; 32]), keygen_v3([2_u8; 32]))
+                }
+
+                #[test]
+                fn test_pop() {
+                    let (sk, other) = keys();
+                    let pk = sk.public();
+                    let proof = sk.pop_prove();
+                    assert!(pk.pop_verify(&proof));
+                    assert!(!pk.pop_verify(&other.pop_prove()));
+                    assert!(!other.public().pop_verify(&proof));
+
+                    // A signature over the same bytes under the `BLS_SIG_` domain is not a
+                    // proof, and a proof is not a signature.
+                    let msg = pk.compress();
+                    let sig = sign(&sk, &msg);
+                    assert!(verify(&pk, &msg, &sig));
+                    assert!(!pk.pop_verify(&sig));
+                    assert!(!verify(&pk, &msg, &proof));
+                }
+
+                const SUITES: [Ciphersuite; 3] = [
+                    Ciphersuite::Basic,
+                    Ciphersuite::MessageAugmentation,
+                    Ciphersuite::ProofOfPossession,
+                ];
+
+                #[test]
+                fn test_ciphersuites() {
+                    let (sk, other) = keys();
+                    let pk = sk.public();
+                    let msg = b"ciphersuite test";
+                    for suite in SUITES {
+                        let sig = sign_with(&sk, msg, suite);
+                        assert!(verify_with(&pk, msg, &sig, suite));
+                        assert!(!verify_with(&pk, b"other message", &sig, suite));
+                        assert!(!verify_with(&other.public(), msg, &sig, suite));
+                        for wrong in SUITES.iter().filter(|&&s| s != suite) {
+                            assert!(!verify_with(&pk, msg, &sig, *wrong));
+                        }
+                    }
+                    assert_eq!(sign(&sk, msg), sign_with(&sk, msg, Ciphersuite::default()));
+                }
+
+                #[test]
+                fn test_message_augmentation() {
+                    let (sk, _) = keys();
+                    let pk = sk.public();
+                    let msg = b"augmented";
+                    let suite = Ciphersuite::MessageAugmentation;
+                    let prefixed = [&pk.compress()[..], &msg[..]].concat();
+
+                    // AUG signs `pk || msg` under its own domain.
+                    let sig = sign_with(&sk, msg, suite);
+                    let mut expected = Signature::default();
+                    expected.sign(&sk, &prefixed, suite.dst());
+                    assert_eq!(sig, expected);
+                    assert!(sig.verify(&pk, &prefixed, suite.dst()));
+                    assert!(!sig.verify(&pk, msg, suite.dst()));
+                }
+            }
+        };
+    }
+
+    variant_tests!(min_pk);
+    variant_tests!(min_sig);
+
+    fn scalar_from_decimal(s: &str) -> crate::points::Scalar {
+        use crate::points::Scalar;
+        s.bytes().fold(Scalar::zero(), |acc, d| {
+            acc * Scalar::from_u64(10) + Scalar::from_u64((d - b'0') as u64)
+        })
+    }
+
+    fn sk_scalar(sk: &SecretKey) -> crate::points::Scalar {
+        crate::points::Scalar::from_bytes_be(&sk.to_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_keygen_v3_deterministic() {
+        let sk = keygen_v3([7_u8; 32]);
+        assert_eq!(sk, keygen_v3([7_u8; 32]));
+        assert_ne!(sk, keygen_v3([8_u8; 32]));
+        assert!(!sk.is_empty());
+    }
+
+    #[test]
+    fn test_secret_key_ct_eq() {
+        let mut bytes = [0_u8; 32];
+        bytes[31] = 1;
+        let one = SecretKey::from_bytes(&bytes).unwrap();
+        bytes[31] = 2;
+        let two = SecretKey::from_bytes(&bytes).unwrap();
+        bytes[0] = 1;
+        let high = SecretKey::from_bytes(&bytes).unwrap();
+
+        assert!(one.ct_eq(&SecretKey::from_bytes(&one.to_bytes()).unwrap()));
+        assert!(!one.ct_eq(&two));
+        assert!(!two.ct_eq(&high));
+        assert_eq!(one, SecretKey::from_bytes(&one.to_bytes()).unwrap());
+        assert_ne!(one, two);
+        assert!(!one.is_empty());
+    }
+
+    #[test]
+    fn test_secret_key_zeroize() {
+        use zeroize::Zeroize;
+
+        // Dropping runs the same `zeroize`.
+        let mut sk = keygen_v3([9_u8; 32]);
+        assert!(!sk.is_empty());
+        sk.zeroize();
+        assert!(sk.is_empty());
+        assert_eq!(sk.to_bytes(), [0; 32]);
+    }
+
+    #[test]
+    fn test_keygen_v4_5_eip2333_vectors() {
+        // EIP-2333 test cases 0 and 1 (master key derivation).
+        let cases = [
+            (
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+                "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+            ),
+            (
+                "3141592653589793238462643383279502884197169399375105820974944592",
+                "29757020647961307431480504535336562678282505419141012933316116377660817309383",
+            ),
+        ];
+        for (seed, expected) in cases {
+            let sk = keygen_v4_5(&hex::decode(seed).unwrap(), KEYGEN_SALT, &[]).unwrap();
+            assert_eq!(sk_scalar(&sk), scalar_from_decimal(expected));
+        }
+        assert_eq!(
+            keygen_v4_5(&[0; 31], KEYGEN_SALT, &[]).unwrap_err(),
+            Error::InvalidIkmLength
+        );
+    }
+
+    #[test]
+    fn test_keygen_v5() {
+        use crate::points::Scalar;
+        use crate::sha256::{hkdf_expand, hkdf_extract};
+
+        let ikm: Vec<u8> = (0..40).collect();
+        let salt = b"my-salt";
+        let info = b"my-key-info";
+
+        // SK = OS2IP(HKDF-Expand(HKDF-Extract(salt, IKM || 0), info || I2OSP(48, 2), 48)) mod r
+        let prk = hkdf_extract(salt, &[&ikm[..], &[0]].concat());
+        let mut okm = [0_u8; 48];
+        hkdf_expand(&prk, &[&info[..], &[0, 48]].concat(), &mut okm).unwrap();
+        let expected = Scalar::from_bytes_be_reduced(&okm);
+
+        let sk = keygen_v5(&ikm, salt, info).unwrap();
+        assert_eq!(sk_scalar(&sk), expected);
+
+        // v4.5 only differs by hashing the salt up front.
+        let hashed = crate::sha256_sum(salt);
+        assert_eq!(
+            keygen_v4_5(&ikm, salt, info).unwrap(),
+            keygen_v5(&ikm, &hashed, info).unwrap()
+        );
+        assert_ne!(sk, keygen_v5(&ikm, salt, b"other-info").unwrap());
+    }
+}
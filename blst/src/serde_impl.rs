@@ -0,0 +1,141 @@
+//! Serde serialization and deserialization for the key and signature types.
+//!
+//! Values are encoded as 0x-prefixed hex strings of their compressed form and
+//! are validated when deserialized.
+
+use crate::{min_pk, min_sig};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use zeroize::Zeroize;
+
+/// Serialize a byte slice as a hex string with 0x prefix
+fn serialize_bytes<S: Serializer>(x: &[u8], s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format!("0x{}", hex::encode(x)))
+}
+
+fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let hex_bytes = s
+        .strip_prefix("0x")
+        .ok_or_else(|| Error::custom("missing 0x prefix"))?;
+    hex::decode(hex_bytes).map_err(Error::custom)
+}
+
+macro_rules! impl_serde_point {
+    ($t:ty) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_bytes(&self.compress(), serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                <$t>::from_bytes(&deserialize_hex(deserializer)?)
+                    .map_err(|err| Error::custom(format_args!("{:?}", err)))
+            }
+        }
+    };
+}
+
+macro_rules! impl_serde_secret_key {
+    ($t:ty) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let mut bytes = self.to_bytes();
+                let result = serialize_bytes(&bytes, serializer);
+                bytes.zeroize();
+                result
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let mut bytes = deserialize_hex(deserializer)?;
+                let result = <$t>::from_bytes(&bytes)
+                    .map_err(|err| Error::custom(format_args!("{:?}", err)));
+                bytes.zeroize();
+                result
+            }
+        }
+    };
+}
+
+impl_serde_point!(min_pk::PublicKey);
+impl_serde_point!(min_pk::Signature);
+impl_serde_secret_key!(min_pk::SecretKey);
+impl_serde_point!(min_sig::PublicKey);
+impl_serde_point!(min_sig::Signature);
+impl_serde_secret_key!(min_sig::SecretKey);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+
+    fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + core::fmt::Debug>(value: &T) {
+        let json = serde_json::to_string(value).unwrap();
+        assert!(json.starts_with("\"0x"));
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+    }
+
+    fn rejects<T: DeserializeOwned + core::fmt::Debug>(hex: &str, reason: &str) {
+        let err = serde_json::from_str::<T>(&format!("\"{}\"", hex)).unwrap_err();
+        assert!(
+            err.to_string().contains(reason),
+            "{:?} rejected with {}",
+            hex,
+            err
+        );
+    }
+
+    /// A compressed point encoding with the compression flag set and the given
+    /// last byte of x; for G2 the byte is the last of the x.c1 half.
+    fn compressed(len: usize, x: u8) -> String {
+        let mut bytes = vec![0_u8; len];
+        bytes[0] = 0x80;
+        bytes[47] = x;
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    macro_rules! variant_tests {
+        ($variant:ident) => {
+            #[test]
+            fn $variant() {
+                use crate::$variant::*;
+
+                let sk = keygen_v3([5_u8; 32]);
+                roundtrip(&sk.public());
+                roundtrip(&sign(&sk, b"serde"));
+                roundtrip(&sk);
+
+                let pk = serde_json::to_string(&sk.public()).unwrap();
+                rejects::<PublicKey>(pk.trim_matches('"').trim_start_matches("0x"), "0x prefix");
+                rejects::<PublicKey>("0xzz", "Invalid character");
+                rejects::<PublicKey>("0x00", "InvalidPubkeyLength");
+                rejects::<Signature>("0x00", "InvalidSignatureLength");
+                rejects::<SecretKey>("0x00", "InvalidSecretKeyLength");
+                rejects::<SecretKey>(&format!("0x{}", "00".repeat(32)), "DeserializeSecretKey");
+            }
+        };
+    }
+
+    variant_tests!(min_pk);
+    variant_tests!(min_sig);
+
+    #[test]
+    fn test_reject_invalid_points() {
+        // x = 1 is not on G1, x = 4 is on the curve but outside the subgroup.
+        rejects::<min_pk::PublicKey>(&compressed(48, 1), "PointNotOnCurve");
+        rejects::<min_pk::PublicKey>(&compressed(48, 4), "PointNotInGroup");
+        rejects::<min_sig::Signature>(&compressed(48, 1), "PointNotOnCurve");
+        rejects::<min_sig::Signature>(&compressed(48, 4), "PointNotInGroup");
+        // x = 0 is not on G2, x = i is on the curve but outside the subgroup.
+        rejects::<min_sig::PublicKey>(&compressed(96, 0), "PointNotOnCurve");
+        rejects::<min_sig::PublicKey>(&compressed(96, 1), "PointNotInGroup");
+        rejects::<min_pk::Signature>(&compressed(96, 0), "PointNotOnCurve");
+        rejects::<min_pk::Signature>(&compressed(96, 1), "PointNotInGroup");
+    }
+}
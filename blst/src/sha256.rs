@@ -0,0 +1,256 @@
+//! SHA-256, HMAC-SHA256 and HKDF (RFC 5869) on top of blst's SHA-256
+//! compression function, so callers don't need a separate hash crate.
+
+use crate::Error;
+
+use zeroize::Zeroize;
+
+extern "C" {
+    // Exported by every blst build (assembly or `no_asm.h`); processes
+    // `blocks` 64-byte blocks from `inp` into the state `h`.
+    fn blst_sha256_block_data_order(h: *mut u32, inp: *const u8, blocks: usize);
+}
+
+pub const DIGEST_LENGTH: usize = 32;
+const BLOCK_LENGTH: usize = 64;
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Incremental SHA-256.
+#[derive(Clone)]
+pub struct Sha256 {
+    h: [u32; 8],
+    buf: [u8; BLOCK_LENGTH],
+    off: usize,
+    len: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self {
+            h: IV,
+            buf: [0; BLOCK_LENGTH],
+            off: 0,
+            len: 0,
+        }
+    }
+}
+
+impl Zeroize for Sha256 {
+    fn zeroize(&mut self) {
+        self.h.zeroize();
+        self.buf.zeroize();
+        self.off = 0;
+        self.len = 0;
+    }
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One-shot digest, equivalent to `sha256_sum`.
+    pub fn digest(data: &[u8]) -> [u8; DIGEST_LENGTH] {
+        let mut h = Self::new();
+        h.update(data);
+        h.finalize()
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+
+        if self.off > 0 {
+            let n = core::cmp::min(BLOCK_LENGTH - self.off, data.len());
+            self.buf[self.off..self.off + n].copy_from_slice(&data[..n]);
+            self.off += n;
+            data = &data[n..];
+            if self.off < BLOCK_LENGTH {
+                return;
+            }
+            self.compress_buf();
+        }
+
+        let blocks = data.len() / BLOCK_LENGTH;
+        if blocks > 0 {
+            unsafe { blst_sha256_block_data_order(self.h.as_mut_ptr(), data.as_ptr(), blocks) };
+            data = &data[blocks * BLOCK_LENGTH..];
+        }
+
+        self.buf[..data.len()].copy_from_slice(data);
+        self.off = data.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; DIGEST_LENGTH] {
+        let bits = self.len.wrapping_mul(8);
+        self.buf[self.off] = 0x80;
+        self.buf[self.off + 1..].iter_mut().for_each(|b| *b = 0);
+        if self.off >= BLOCK_LENGTH - 8 {
+            self.compress_buf();
+            self.buf = [0; BLOCK_LENGTH];
+        }
+        self.buf[BLOCK_LENGTH - 8..].copy_from_slice(&bits.to_be_bytes());
+        self.compress_buf();
+
+        let mut out = [0_u8; DIGEST_LENGTH];
+        for (chunk, word) in out.chunks_mut(4).zip(self.h.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        self.zeroize();
+        out
+    }
+
+    fn compress_buf(&mut self) {
+        unsafe { blst_sha256_block_data_order(self.h.as_mut_ptr(), self.buf.as_ptr(), 1) };
+        self.off = 0;
+    }
+}
+
+/// Incremental HMAC-SHA256 (RFC 2104).
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256,
+}
+
+impl HmacSha256 {
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = [0_u8; BLOCK_LENGTH];
+        if key.len() > BLOCK_LENGTH {
+            block[..DIGEST_LENGTH].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut inner = Sha256::new();
+        let mut outer = Sha256::new();
+        block.iter_mut().for_each(|b| *b ^= 0x36);
+        inner.update(&block);
+        block.iter_mut().for_each(|b| *b ^= 0x36 ^ 0x5c);
+        outer.update(&block);
+        block.zeroize();
+
+        Self { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(mut self) -> [u8; DIGEST_LENGTH] {
+        let inner = core::mem::take(&mut self.inner).finalize();
+        let mut outer = core::mem::take(&mut self.outer);
+        outer.update(&inner);
+        outer.finalize()
+    }
+}
+
+impl Drop for HmacSha256 {
+    fn drop(&mut self) {
+        self.inner.zeroize();
+        self.outer.zeroize();
+    }
+}
+
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; DIGEST_LENGTH] {
+    let mut mac = HmacSha256::new(key);
+    mac.update(data);
+    mac.finalize()
+}
+
+/// HKDF-Extract: derives a pseudorandom key from `ikm`.
+pub fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> [u8; DIGEST_LENGTH] {
+    hmac_sha256(salt, ikm)
+}
+
+/// HKDF-Expand: fills `okm` from the pseudorandom key `prk`. At most
+/// `255 * 32` bytes can be derived.
+pub fn hkdf_expand(prk: &[u8], info: &[u8], okm: &mut [u8]) -> Result<(), Error> {
+    if okm.len() > 255 * DIGEST_LENGTH {
+        return Err(Error::InvalidOutputLength);
+    }
+    let mut t = [0_u8; DIGEST_LENGTH];
+    for (i, chunk) in okm.chunks_mut(DIGEST_LENGTH).enumerate() {
+        let mut mac = HmacSha256::new(prk);
+        if i > 0 {
+            mac.update(&t);
+        }
+        mac.update(info);
+        mac.update(&[i as u8 + 1]);
+        t = mac.finalize();
+        chunk.copy_from_slice(&t[..chunk.len()]);
+    }
+    t.zeroize();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            hex::encode(Sha256::digest(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(Sha256::digest(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex::encode(Sha256::digest(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+
+        let data: Vec<u8> = (0..1000_u32).map(|i| (i * 31) as u8).collect();
+        for split in [0, 1, 55, 56, 63, 64, 65, 128, 999, 1000] {
+            let mut h = Sha256::new();
+            h.update(&data[..split]);
+            h.update(&data[split..]);
+            assert_eq!(h.finalize(), crate::sha256_sum(&data), "split {}", split);
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test cases 2 and 6.
+        assert_eq!(
+            hex::encode(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert_eq!(
+            hex::encode(hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_hkdf() {
+        // RFC 5869 test case 1.
+        let salt: Vec<u8> = (0x00..=0x0c).collect();
+        let info: Vec<u8> = (0xf0..=0xf9).collect();
+        let prk = hkdf_extract(&salt, &[0x0b; 22]);
+        assert_eq!(
+            hex::encode(prk),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+        let mut okm = [0_u8; 42];
+        hkdf_expand(&prk, &info, &mut okm).unwrap();
+        assert_eq!(
+            hex::encode(okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert_eq!(
+            hkdf_expand(&prk, &info, &mut [0; 255 * 32 + 1]),
+            Err(Error::InvalidOutputLength)
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! t-of-n threshold BLS on top of the `min_pk` types.
+//!
+//! A dealer splits a secret key with a random polynomial of degree `t - 1`
+//! (Shamir), publishes Feldman commitments to the coefficients in G1 so that
+//! every participant can check its share, and any `t` partial signatures (or
+//! public key shares) are combined into the group one by Lagrange
+//! interpolation at zero.
+
+use crate::bindings::*;
+use crate::min_pk::{PublicKey, SecretKey, Signature};
+use crate::Error;
+
+use alloc::vec::Vec;
+use zeroize::Zeroize;
+
+const COEFFICIENT_INFO: &[u8] = b"BLS_THRESHOLD_COEFFICIENT_";
+
+/// The secret sharing polynomial of a dealer. `coefficients[0]` is the
+/// shared secret. Coefficients are wiped on drop.
+pub struct SecretPolynomial {
+    coefficients: Vec<blst_fr>,
+}
+
+/// The share of participant `index` (starting at 1), i.e. the dealer's
+/// polynomial evaluated at `index`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SecretKeyShare {
+    pub index: u64,
+    pub key: SecretKey,
+}
+
+/// Feldman commitments `g1 * a_j` to every coefficient of a
+/// [`SecretPolynomial`]. The first one is the group public key, so a
+/// commitment is never empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commitment(Vec<blst_p1_affine>);
+
+impl Drop for SecretPolynomial {
+    fn drop(&mut self) {
+        for c in self.coefficients.iter_mut() {
+            c.l.zeroize();
+        }
+    }
+}
+
+impl SecretPolynomial {
+    /// Builds a polynomial of degree `threshold - 1` hiding `secret`. The
+    /// remaining coefficients are derived deterministically from `ikm`
+    /// (at least 32 bytes of secret randomness) with the IETF key generation
+    /// procedure, one `info` string per coefficient.
+    pub fn new(secret: &SecretKey, threshold: usize, ikm: &[u8]) -> Result<Self, Error> {
+        if threshold == 0 {
+            return Err(Error::InvalidThreshold);
+        }
+        if ikm.len() < 32 {
+            return Err(Error::InvalidIkmLength);
+        }
+        let mut coefficients = Vec::with_capacity(threshold);
+        coefficients.push(fr_from_secret_key(secret));
+        for i in 1..threshold {
+            let mut info = [0_u8; COEFFICIENT_INFO.len() + 4];
+            info[..COEFFICIENT_INFO.len()].copy_from_slice(COEFFICIENT_INFO);
+            info[COEFFICIENT_INFO.len()..].copy_from_slice(&(i as u32).to_be_bytes());
+            let mut scalar = blst_scalar::default();
+            let mut coefficient = blst_fr::default();
+            unsafe {
+                blst_keygen(
+                    &mut scalar,
+                    ikm.as_ptr(),
+                    ikm.len(),
+                    info.as_ptr(),
+                    info.len(),
+                );
+                blst_fr_from_scalar(&mut coefficient, &scalar);
+            }
+            scalar.b.zeroize();
+            coefficients.push(coefficient);
+        }
+        Ok(Self { coefficients })
+    }
+
+    /// Uses the given keys as coefficients, starting with the shared secret.
+    pub fn from_coefficients(coefficients: &[SecretKey]) -> Result<Self, Error> {
+        if coefficients.is_empty() {
+            return Err(Error::InvalidThreshold);
+        }
+        Ok(Self {
+            coefficients: coefficients.iter().map(fr_from_secret_key).collect(),
+        })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn secret(&self) -> SecretKey {
+        secret_key_from_fr(&self.coefficients[0])
+    }
+
+    /// Evaluates the polynomial at `index` with Horner's rule.
+    pub fn share(&self, index: u64) -> Result<SecretKeyShare, Error> {
+        if index == 0 {
+            return Err(Error::InvalidShareIndex);
+        }
+        let x = fr_from_u64(index);
+        let mut acc = blst_fr::default();
+        for c in self.coefficients.iter().rev() {
+            unsafe {
+                blst_fr_mul(&mut acc, &acc, &x);
+                blst_fr_add(&mut acc, &acc, c);
+            }
+        }
+        let key = secret_key_from_fr(&acc);
+        acc.l.zeroize();
+        Ok(SecretKeyShare { index, key })
+    }
+
+    /// Returns the shares of participants `1..=n`.
+    pub fn shares(&self, n: u64) -> Result<Vec<SecretKeyShare>, Error> {
+        if (n as usize) < self.threshold() {
+            return Err(Error::InvalidThreshold);
+        }
+        (1..=n).map(|index| self.share(index)).collect()
+    }
+
+    pub fn commitment(&self) -> Commitment {
+        let mut points = Vec::with_capacity(self.coefficients.len());
+        for c in self.coefficients.iter() {
+            let mut scalar = blst_scalar::default();
+            let mut p = blst_p1::default();
+            let mut affine = blst_p1_affine::default();
+            unsafe {
+                blst_scalar_from_fr(&mut scalar, c);
+                blst_sk_to_pk_in_g1(&mut p, &scalar);
+                blst_p1_to_affine(&mut affine, &p);
+            }
+            scalar.b.zeroize();
+            points.push(affine);
+        }
+        Commitment(points)
+    }
+}
+
+impl Commitment {
+    pub fn threshold(&self) -> usize {
+        self.0.len()
+    }
+
+    /// The public key of the shared secret.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.0[0])
+    }
+
+    /// Evaluates the committed polynomial in the exponent at `index`, which
+    /// yields the public key matching that participant's share.
+    pub fn public_key_share(&self, index: u64) -> Result<PublicKey, Error> {
+        if index == 0 {
+            return Err(Error::InvalidShareIndex);
+        }
+        let x = fr_from_u64(index);
+        let mut acc = blst_p1::default();
+        for c in self.0.iter().rev() {
+            acc = p1_mult(&acc, &x);
+            unsafe { blst_p1_add_or_double_affine(&mut acc, &acc, c) };
+        }
+        Ok(PublicKey(p1_to_affine(&acc)))
+    }
+
+    /// Feldman verification: `g1 * share == sum(C_j * index^j)`.
+    pub fn verify_share(&self, share: &SecretKeyShare) -> bool {
+        match self.public_key_share(share.index) {
+            Ok(pk) => pk == share.key.public(),
+            Err(_) => false,
+        }
+    }
+
+    pub fn points(&self) -> &[blst_p1_affine] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<PublicKey>> for Commitment {
+    type Error = Error;
+
+    fn try_from(points: Vec<PublicKey>) -> Result<Self, Error> {
+        if points.is_empty() {
+            return Err(Error::InvalidThreshold);
+        }
+        Ok(Self(points.into_iter().map(|pk| pk.0).collect()))
+    }
+}
+
+/// Recovers the shared secret from at least `threshold` shares.
+pub fn recover_secret_key(shares: &[SecretKeyShare]) -> Result<SecretKey, Error> {
+    let indices: Vec<u64> = shares.iter().map(|s| s.index).collect();
+    let lambdas = lagrange_coefficients(&indices)?;
+    let mut acc = blst_fr::default();
+    for (share, lambda) in shares.iter().zip(lambdas.iter()) {
+        let mut term = fr_from_secret_key(&share.key);
+        unsafe {
+            blst_fr_mul(&mut term, &term, lambda);
+            blst_fr_add(&mut acc, &acc, &term);
+        }
+        term.l.zeroize();
+    }
+    let sk = secret_key_from_fr(&acc);
+    acc.l.zeroize();
+    Ok(sk)
+}
+
+/// Interpolates public key shares `(index, pk)` into the group public key.
+pub fn combine_public_keys(parts: &[(u64, PublicKey)]) -> Result<PublicKey, Error> {
+    let indices: Vec<u64> = parts.iter().map(|(index, _)| *index).collect();
+    let lambdas = lagrange_coefficients(&indices)?;
+    let mut acc = blst_p1::default();
+    for ((_, pk), lambda) in parts.iter().zip(lambdas.iter()) {
+        let mut p = blst_p1::default();
+        unsafe { blst_p1_from_affine(&mut p, &pk.0) };
+        let term = p1_mult(&p, lambda);
+        unsafe { blst_p1_add_or_double(&mut acc, &acc, &term) };
+    }
+    Ok(PublicKey(p1_to_affine(&acc)))
+}
+
+/// Interpolates partial signatures `(index, sig)` over the same message into
+/// the group signature.
+pub fn combine_signatures(parts: &[(u64, Signature)]) -> Result<Signature, Error> {
+    let indices: Vec<u64> = parts.iter().map(|(index, _)| *index).collect();
+    let lambdas = lagrange_coefficients(&indices)?;
+    let mut acc = blst_p2::default();
+    for ((_, sig), lambda) in parts.iter().zip(lambdas.iter()) {
+        let mut p = blst_p2::default();
+        let mut term = blst_p2::default();
+        let scalar = scalar_from_fr(lambda);
+        unsafe {
+            blst_p2_from_affine(&mut p, &sig.0);
+            blst_p2_mult(&mut term, &p, scalar.b.as_ptr(), 255);
+            blst_p2_add_or_double(&mut acc, &acc, &term);
+        }
+    }
+    let mut affine = blst_p2_affine::default();
+    unsafe { blst_p2_to_affine(&mut affine, &acc) };
+    Ok(Signature(affine))
+}
+
+/// Lagrange basis polynomials evaluated at zero for the given indices:
+/// `lambda_i = prod_{j != i} x_j / (x_j - x_i)`.
+pub fn lagrange_coefficients(indices: &[u64]) -> Result<Vec<blst_fr>, Error> {
+    if indices.is_empty() {
+        return Err(Error::InvalidThreshold);
+    }
+    for (i, x) in indices.iter().enumerate() {
+        if *x == 0 {
+            return Err(Error::InvalidShareIndex);
+        }
+        if indices[..i].contains(x) {
+            return Err(Error::DuplicateShareIndex);
+        }
+    }
+    let xs: Vec<blst_fr> = indices.iter().map(|x| fr_from_u64(*x)).collect();
+    let mut lambdas = Vec::with_capacity(xs.len());
+    for (i, xi) in xs.iter().enumerate() {
+        let mut num = fr_from_u64(1);
+        let mut den = fr_from_u64(1);
+        for (j, xj) in xs.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            let mut diff = blst_fr::default();
+            unsafe {
+                blst_fr_mul(&mut num, &num, xj);
+                blst_fr_sub(&mut diff, xj, xi);
+                blst_fr_mul(&mut den, &den, &diff);
+            }
+        }
+        let mut lambda = blst_fr::default();
+        unsafe {
+            blst_fr_inverse(&mut den, &den);
+            blst_fr_mul(&mut lambda, &num, &den);
+        }
+        lambdas.push(lambda);
+    }
+    Ok(lambdas)
+}
+
+fn fr_from_u64(x: u64) -> blst_fr {
+    let limbs = [x, 0, 0, 0];
+    let mut out = blst_fr::default();
+    unsafe { blst_fr_from_uint64(&mut out, limbs.as_ptr()) };
+    out
+}
+
+fn fr_from_secret_key(sk: &SecretKey) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe { blst_fr_from_scalar(&mut out, &sk.0) };
+    out
+}
+
+fn scalar_from_fr(fr: &blst_fr) -> blst_scalar {
+    let mut out = blst_scalar::default();
+    unsafe { blst_scalar_from_fr(&mut out, fr) };
+    out
+}
+
+fn secret_key_from_fr(fr: &blst_fr) -> SecretKey {
+    SecretKey(scalar_from_fr(fr))
+}
+
+fn p1_mult(p: &blst_p1, fr: &blst_fr) -> blst_p1 {
+    let scalar = scalar_from_fr(fr);
+    let mut out = blst_p1::default();
+    unsafe { blst_p1_mult(&mut out, p, scalar.b.as_ptr(), 255) };
+    out
+}
+
+fn p1_to_affine(p: &blst_p1) -> blst_p1_affine {
+    let mut out = blst_p1_affine::default();
+    unsafe { blst_p1_to_affine(&mut out, p) };
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::min_pk::{keygen_v3, sign, verify};
+
+    const MSG: &[u8] = b"distributed validator duty";
+
+    fn dealer() -> (SecretKey, SecretPolynomial) {
+        let secret = keygen_v3([1_u8; 32]);
+        let poly = SecretPolynomial::new(&secret, 3, &[2_u8; 32]).unwrap();
+        (secret, poly)
+    }
+
+    #[test]
+    fn test_shares_match_commitment() {
+        let (secret, poly) = dealer();
+        let commitment = poly.commitment();
+        assert_eq!(commitment.threshold(), 3);
+        assert_eq!(commitment.public_key(), secret.public());
+
+        let shares = poly.shares(5).unwrap();
+        for share in shares.iter() {
+            assert!(commitment.verify_share(share));
+            assert_eq!(
+                commitment.public_key_share(share.index).unwrap(),
+                share.key.public()
+            );
+        }
+
+        let forged = SecretKeyShare {
+            index: 2,
+            key: SecretKey::from_bytes(&shares[0].key.to_bytes()).unwrap(),
+        };
+        assert!(!commitment.verify_share(&forged));
+    }
+
+    #[test]
+    fn test_deterministic_split() {
+        let (_, a) = dealer();
+        let (_, b) = dealer();
+        assert_eq!(a.share(4).unwrap(), b.share(4).unwrap());
+        let other = SecretPolynomial::new(&keygen_v3([1_u8; 32]), 3, &[3_u8; 32]).unwrap();
+        assert_ne!(a.share(4).unwrap(), other.share(4).unwrap());
+        assert_eq!(a.share(0).unwrap_err(), Error::InvalidShareIndex);
+        assert_eq!(
+            SecretPolynomial::new(&keygen_v3([1_u8; 32]), 3, &[3_u8; 31]).err(),
+            Some(Error::InvalidIkmLength)
+        );
+    }
+
+    #[test]
+    fn test_combine_any_subset() {
+        let (secret, poly) = dealer();
+        let commitment = poly.commitment();
+        let shares = poly.shares(5).unwrap();
+        let expected = sign(&secret, MSG);
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let partials: Vec<(u64, Signature)> = subset
+                .iter()
+                .map(|&i| (shares[i].index, sign(&shares[i].key, MSG)))
+                .collect();
+            let group_sig = combine_signatures(&partials).unwrap();
+            assert_eq!(group_sig, expected);
+            assert!(verify(&commitment.public_key(), MSG, &group_sig));
+
+            let pks: Vec<(u64, PublicKey)> = subset
+                .iter()
+                .map(|&i| (shares[i].index, shares[i].key.public()))
+                .collect();
+            assert_eq!(combine_public_keys(&pks).unwrap(), secret.public());
+        }
+
+        let two: Vec<(u64, Signature)> = shares[..2]
+            .iter()
+            .map(|s| (s.index, sign(&s.key, MSG)))
+            .collect();
+        assert_ne!(combine_signatures(&two).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_recover_secret_key() {
+        let (secret, poly) = dealer();
+        let shares = poly.shares(5).unwrap();
+        assert_eq!(recover_secret_key(&shares[1..4]).unwrap(), secret);
+        assert_eq!(recover_secret_key(&shares).unwrap(), secret);
+        assert_eq!(poly.secret(), secret);
+
+        let dup = [(1, sign(&shares[0].key, MSG)), (1, sign(&shares[0].key, MSG))];
+        assert_eq!(combine_signatures(&dup).unwrap_err(), Error::DuplicateShareIndex);
+        assert_eq!(combine_signatures(&[]).unwrap_err(), Error::InvalidThreshold);
+    }
+
+    #[test]
+    fn test_commitment_from_public_keys() {
+        let (secret, poly) = dealer();
+        let commitment = poly.commitment();
+        let pks: Vec<PublicKey> = commitment.points().iter().map(|p| PublicKey(*p)).collect();
+        let rebuilt = Commitment::try_from(pks).unwrap();
+        assert_eq!(rebuilt, commitment);
+        assert_eq!(rebuilt.public_key(), secret.public());
+        assert_eq!(
+            Commitment::try_from(Vec::new()).unwrap_err(),
+            Error::InvalidThreshold
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Runs precompile test vectors in the go-ethereum JSON format, shared by the
+//! `eip2537` and `bn254` tests.
+//!
+//! A suite `<name>.json` is an array of `{"Input", "Expected", "Name", "Gas"}`
+//! cases. The optional `fail-<name>.json` holds `{"Input", "ExpectedError",
+//! "Name"}` cases that the precompile must reject.
+
+use crate::Error;
+
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A precompile taking the raw call data.
+pub(crate) type Precompile = fn(&[u8]) -> Result<Vec<u8>, Error>;
+
+/// The directory the vectors for `suite` are dropped into, under `tests/`.
+pub(crate) fn dir(suite: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(suite)
+}
+
+fn load(path: &Path) -> Option<Vec<Value>> {
+    let json = fs::read(path).ok()?;
+    match serde_json::from_slice(&json) {
+        Ok(Value::Array(cases)) => Some(cases),
+        _ => panic!("{}: expected an array of test cases", path.display()),
+    }
+}
+
+fn field<'a>(case: &'a Value, key: &str) -> &'a str {
+    case[key]
+        .as_str()
+        .unwrap_or_else(|| panic!("missing {} in {}", key, case))
+}
+
+fn bytes(case: &Value, key: &str) -> Vec<u8> {
+    hex::decode(field(case, key).trim_start_matches("0x")).unwrap()
+}
+
+/// Runs `f` over `<dir>/<name>.json` and, if present, `<dir>/fail-<name>.json`.
+/// Outputs must match exactly. A failing case must return the error that
+/// `expected_error` maps its message to, or any error if it maps to `None`.
+/// Panics if the suite file is missing so a misnamed file can't pass quietly.
+pub(crate) fn run(
+    dir: &Path,
+    name: &str,
+    f: Precompile,
+    expected_error: fn(&str) -> Option<Error>,
+) {
+    let path = dir.join(format!("{}.json", name));
+    let cases = load(&path).unwrap_or_else(|| panic!("{} is missing", path.display()));
+    assert!(!cases.is_empty(), "{} has no cases", path.display());
+    for case in &cases {
+        let out = f(&bytes(case, "Input"));
+        assert_eq!(
+            out,
+            Ok(bytes(case, "Expected")),
+            "{}: {}",
+            name,
+            field(case, "Name")
+        );
+    }
+
+    let fail = dir.join(format!("fail-{}.json", name));
+    for case in load(&fail).unwrap_or_default() {
+        let name = field(&case, "Name");
+        let out = f(&bytes(&case, "Input"));
+        match expected_error(field(&case, "ExpectedError")) {
+            Some(err) => assert_eq!(out, Err(err), "fail-{}", name),
+            None => assert!(out.is_err(), "fail-{} was accepted", name),
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use std::env;
 use std::path::PathBuf;
 
+/// The C preprocessor overrides for the minimal preset. The header derives the other sizes from
+/// them, including `FIELD_ELEMENTS_PER_CELL`.
+const MINIMAL_PRESET: [(&str, &str); 1] = [("FIELD_ELEMENTS_PER_BLOB", "4")];
+
 fn main() {
     let cargo_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
     /*
@@ -31,11 +35,24 @@ fn main() {
         cc.flag("/std:c11");
     }
 
+    // The pure-Rust backend replaces the C library entirely.
+    let rust_backend = env::var_os("CARGO_FEATURE_RUST_BACKEND").is_some();
+
+    // The minimal preset shrinks blobs to 4 field elements. Cells are shrunk with them so that an
+    // extended blob still splits into several cells. These must match the Rust constants.
+    if env::var_os("CARGO_FEATURE_MINIMAL_SPEC").is_some() {
+        for (name, value) in MINIMAL_PRESET {
+            cc.define(name, value);
+        }
+    }
+
     cc.include(blst_headers_dir.clone());
     cc.warnings(false);
     cc.file(c_src_dir.join("c_kzg_4844.c"));
 
-    cc.try_compile("ckzg").expect("Failed to compile ckzg");
+    if !rust_backend {
+        cc.try_compile("ckzg").expect("Failed to compile ckzg");
+    }
 
     #[cfg(feature = "generate-bindings")]
     {
@@ -49,7 +66,9 @@ fn main() {
     }
 
     // Finally, tell cargo this provides ckzg/ckzg_min
-    println!("cargo:rustc-link-lib=ckzg");
+    if !rust_backend {
+        println!("cargo:rustc-link-lib=ckzg");
+    }
 }
 
 #[cfg(feature = "generate-bindings")]
@@ -65,7 +84,11 @@ fn make_bindings(header_path: &str, blst_headers_dir: &str, bindings_out_path: &
                 | "BYTES_PER_COMMITMENT"
                 | "BYTES_PER_PROOF"
                 | "BYTES_PER_FIELD_ELEMENT"
-                | "BYTES_PER_BLOB" => Some(bindgen::callbacks::IntKind::Custom {
+                | "BYTES_PER_BLOB"
+                | "FIELD_ELEMENTS_PER_EXT_BLOB"
+                | "FIELD_ELEMENTS_PER_CELL"
+                | "CELLS_PER_EXT_BLOB"
+                | "BYTES_PER_CELL" => Some(bindgen::callbacks::IntKind::Custom {
                     name: "usize",
                     is_signed: false,
                 }),
@@ -110,6 +133,8 @@ fn make_bindings(header_path: &str, blst_headers_dir: &str, bindings_out_path: &
         .derive_hash(true)
         // Blobs are big, we don't want rust to liberally copy them around.
         .no_copy("Blob")
+        // Same for cells, which are handled in arrays of up to CELLS_PER_EXT_BLOB.
+        .no_copy("Cell")
         // Do not make fields public. If we want to modify them we can create setters/mutable
         // getters when necessary.
         .default_visibility(bindgen::FieldVisibilityKind::Private)
@@ -128,6 +153,7 @@ fn make_bindings(header_path: &str, blst_headers_dir: &str, bindings_out_path: &
 
     let mut bindings = bindings.to_string();
     bindings = replace_ckzg_ret_repr(bindings);
+    bindings = split_preset_constants(bindings);
     std::fs::write(bindings_out_path, bindings).expect("Failed to write bindings");
 }
 
@@ -163,4 +189,43 @@ fn replace_ckzg_ret_repr(mut bindings: String) -> String {
     bindings.replace_range(repr_start..repr_start + repr_to_replace.len(), "#[repr(C)]");
 
     bindings
-}
\ No newline at end of file
+}
+
+/// The constants that depend on the preset, with their mainnet and minimal values. The minimal
+/// `FIELD_ELEMENTS_PER_CELL` is not a consensus-spec value: cells are 64 field elements in every
+/// spec preset, and the header falls back to half a blob when the blob is smaller than that.
+#[cfg(feature = "generate-bindings")]
+const PRESET_CONSTANTS: [(&str, usize, usize); 6] = [
+    ("FIELD_ELEMENTS_PER_BLOB", 4096, 4),
+    ("BYTES_PER_BLOB", 131072, 128),
+    ("FIELD_ELEMENTS_PER_EXT_BLOB", 8192, 8),
+    ("FIELD_ELEMENTS_PER_CELL", 64, 2),
+    ("CELLS_PER_EXT_BLOB", 128, 4),
+    ("BYTES_PER_CELL", 2048, 64),
+];
+
+// Bindings are generated from the mainnet header. The preset-dependent constants are split on
+// the `minimal-spec` feature, and the blob and cell byte arrays are sized by those constants
+// rather than by the literals bindgen expands them to, so the same file serves both presets.
+#[cfg(feature = "generate-bindings")]
+fn split_preset_constants(mut bindings: String) -> String {
+    for (name, mainnet, minimal) in PRESET_CONSTANTS {
+        let constant = format!("pub const {name}: usize = {mainnet};\n");
+        assert!(
+            bindings.contains(&constant),
+            "Could not find {name} in bindings"
+        );
+        bindings = bindings.replacen(
+            &constant,
+            &format!(
+                "#[cfg(not(feature = \"minimal-spec\"))]\n{constant}\
+                 #[cfg(feature = \"minimal-spec\")]\npub const {name}: usize = {minimal};\n"
+            ),
+            1,
+        );
+        if name.starts_with("BYTES_PER_") {
+            bindings = bindings.replace(&format!("[u8; {mainnet}usize]"), &format!("[u8; {name}]"));
+        }
+    }
+    bindings
+}
@@ -17,7 +17,8 @@
 /**
  * @file c_kzg_4844.c
  *
- * Minimal implementation of the polynomial commitments API for EIP-4844.
+ * Minimal implementation of the polynomial commitments API for EIP-4844 and
+ * the cell (data availability sampling) API for EIP-7594.
  */
 #include "c_kzg_4844.h"
 
@@ -66,6 +67,9 @@ static const char *FIAT_SHAMIR_PROTOCOL_DOMAIN = "FSBLOBVERIFY_V1_";
 /** The domain separator for a random challenge. */
 static const char *RANDOM_CHALLENGE_KZG_BATCH_DOMAIN = "RCKZGBATCH___V1_";
 
+/** The domain separator for verify_cell_kzg_proof_batch's random challenge. */
+static const char *RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN = "RCKZGCBATCH__V1_";
+
 /** Length of the domain strings above. */
 #define DOMAIN_STR_LENGTH 16
 
@@ -81,6 +85,12 @@ static const char *RANDOM_CHALLENGE_KZG_BATCH_DOMAIN = "RCKZGBATCH___V1_";
 /** The number of g2 points in a trusted setup. */
 #define TRUSTED_SETUP_NUM_G2_POINTS 65
 
+/**
+ * The coset shift used when evaluating over a coset of the extended domain.
+ * Any element outside the domain works; this matches the consensus specs.
+ */
+#define RECOVERY_SHIFT_FACTOR 7
+
 // clang-format off
 
 /** Deserialized form of the G1 identity/infinity point. */
@@ -1010,6 +1020,36 @@ out:
     return ret;
 }
 
+/**
+ * Evaluate the polynomial represented by a blob at a point.
+ *
+ * @param[out] y_out   The evaluation of the polynomial at @p z_bytes
+ * @param[in]  blob    The blob (polynomial) to evaluate
+ * @param[in]  z_bytes The point to evaluate at
+ * @param[in]  s       The trusted setup
+ */
+C_KZG_RET evaluate_blob_at(
+    Bytes32 *y_out,
+    const Blob *blob,
+    const Bytes32 *z_bytes,
+    const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    Polynomial polynomial;
+    fr_t frz, fry;
+
+    ret = blob_to_polynomial(&polynomial, blob);
+    if (ret != C_KZG_OK) goto out;
+    ret = bytes_to_bls_field(&frz, z_bytes);
+    if (ret != C_KZG_OK) goto out;
+    ret = evaluate_polynomial_in_evaluation_form(&fry, &polynomial, &frz, s);
+    if (ret != C_KZG_OK) goto out;
+    bytes_from_bls_field(y_out, &fry);
+
+out:
+    return ret;
+}
+
 /**
  * Helper function for compute_kzg_proof() and
  * compute_blob_kzg_proof().
@@ -1575,51 +1615,49 @@ static C_KZG_RET expand_root_of_unity(
 /**
  * Initialize the roots of unity.
  *
- * @remark `roots_of_unity_out` may be modified even if there's an error.
+ * @remark `s->roots_of_unity`, `s->expanded_roots_of_unity` and
+ *     `s->reverse_roots_of_unity` may be modified even if there's an error.
  *
- * @param[out] roots_of_unity_out The roots of unity
- * @param[in]  max_scale          Log base 2 of the number of roots of unity to
- *                                be initialized
+ * @param[in,out] s The trusted setup, with `max_width` set and the root
+ *                  arrays allocated
  */
-static C_KZG_RET compute_roots_of_unity(
-    fr_t *roots_of_unity_out, uint32_t max_scale
-) {
+static C_KZG_RET compute_roots_of_unity(KZGSettings *s) {
     C_KZG_RET ret;
-    uint64_t max_width;
     fr_t root_of_unity;
-    fr_t *expanded_roots = NULL;
-
-    /* Calculate the max width */
-    max_width = 1ULL << max_scale;
 
     /* Get the root of unity */
+    uint32_t max_scale = log2_pow2((uint32_t)s->max_width);
     CHECK(max_scale < NUM_ELEMENTS(SCALE2_ROOT_OF_UNITY));
     blst_fr_from_uint64(&root_of_unity, SCALE2_ROOT_OF_UNITY[max_scale]);
 
-    /*
-     * Allocate an array to store the expanded roots of unity. We do this
-     * instead of re-using roots_of_unity_out because the expansion requires
-     * max_width+1 elements.
-     */
-    ret = new_fr_array(&expanded_roots, max_width + 1);
-    if (ret != C_KZG_OK) goto out;
-
     /* Populate the roots of unity */
-    ret = expand_root_of_unity(expanded_roots, &root_of_unity, max_width);
-    if (ret != C_KZG_OK) goto out;
+    ret = expand_root_of_unity(
+        s->expanded_roots_of_unity, &root_of_unity, s->max_width
+    );
+    if (ret != C_KZG_OK) return ret;
+
+    /* The inverse of w^i is w^(max_width - i) */
+    for (uint64_t i = 0; i <= s->max_width; i++) {
+        s->reverse_roots_of_unity[i] =
+            s->expanded_roots_of_unity[s->max_width - i];
+    }
 
     /* Copy all but the last root to the roots of unity */
-    memcpy(roots_of_unity_out, expanded_roots, sizeof(fr_t) * max_width);
+    memcpy(
+        s->roots_of_unity,
+        s->expanded_roots_of_unity,
+        sizeof(fr_t) * s->max_width
+    );
 
     /* Permute the roots of unity */
-    ret = bit_reversal_permutation(roots_of_unity_out, sizeof(fr_t), max_width);
-    if (ret != C_KZG_OK) goto out;
-
-out:
-    c_kzg_free(expanded_roots);
-    return ret;
+    return bit_reversal_permutation(
+        s->roots_of_unity, sizeof(fr_t), s->max_width
+    );
 }
 
+/* Forward function declarations */
+static C_KZG_RET init_fk20_columns(KZGSettings *s);
+
 /**
  * Free a trusted setup (KZGSettings).
  *
@@ -1631,8 +1669,12 @@ void free_trusted_setup(KZGSettings *s) {
     if (s == NULL) return;
     s->max_width = 0;
     c_kzg_free(s->roots_of_unity);
+    c_kzg_free(s->expanded_roots_of_unity);
+    c_kzg_free(s->reverse_roots_of_unity);
     c_kzg_free(s->g1_values);
+    c_kzg_free(s->g1_values_monomial);
     c_kzg_free(s->g2_values);
+    c_kzg_free(s->x_ext_fft_columns);
 }
 
 /**
@@ -1662,20 +1704,46 @@ static C_KZG_RET is_trusted_setup_in_lagrange_form(
     return is_monomial_form ? C_KZG_BADARGS : C_KZG_OK;
 }
 
+/**
+ * Convert an array of compressed G1 points into projective points.
+ *
+ * @param[out] out   The decompressed points, length @p n
+ * @param[in]  bytes The compressed points, `n * BYTES_PER_G1` bytes
+ * @param[in]  n     The number of points
+ */
+static C_KZG_RET g1_values_from_bytes(
+    g1_t *out, const uint8_t *bytes, size_t n
+) {
+    for (size_t i = 0; i < n; i++) {
+        blst_p1_affine g1_affine;
+        BLST_ERROR err = blst_p1_uncompress(
+            &g1_affine, &bytes[BYTES_PER_G1 * i]
+        );
+        if (err != BLST_SUCCESS) return C_KZG_BADARGS;
+        blst_p1_from_affine(&out[i], &g1_affine);
+    }
+    return C_KZG_OK;
+}
+
 /**
  * Load trusted setup into a KZGSettings.
  *
  * @remark Free after use with free_trusted_setup().
  *
- * @param[out] out      Pointer to the stored trusted setup data
- * @param[in]  g1_bytes Array of G1 points in Lagrange form
- * @param[in]  n1       Number of `g1` points in g1_bytes
- * @param[in]  g2_bytes Array of G2 points in monomial form
- * @param[in]  n2       Number of `g2` points in g2_bytes
+ * @remark If @p g1_monomial_bytes is NULL, only the EIP-4844 functions can
+ *     be used with the result; the cell functions return C_KZG_BADARGS.
+ *
+ * @param[out] out               Pointer to the stored trusted setup data
+ * @param[in]  g1_monomial_bytes Array of G1 points in monomial form, or NULL
+ * @param[in]  g1_lagrange_bytes Array of G1 points in Lagrange form
+ * @param[in]  n1                Number of `g1` points in each G1 array
+ * @param[in]  g2_bytes          Array of G2 points in monomial form
+ * @param[in]  n2                Number of `g2` points in g2_bytes
  */
 C_KZG_RET load_trusted_setup(
     KZGSettings *out,
-    const uint8_t *g1_bytes,
+    const uint8_t *g1_monomial_bytes,
+    const uint8_t *g1_lagrange_bytes,
     size_t n1,
     const uint8_t *g2_bytes,
     size_t n2
@@ -1684,40 +1752,48 @@ C_KZG_RET load_trusted_setup(
 
     out->max_width = 0;
     out->roots_of_unity = NULL;
+    out->expanded_roots_of_unity = NULL;
+    out->reverse_roots_of_unity = NULL;
     out->g1_values = NULL;
+    out->g1_values_monomial = NULL;
     out->g2_values = NULL;
+    out->x_ext_fft_columns = NULL;
 
     /* Sanity check in case this is called directly */
     CHECK(n1 == TRUSTED_SETUP_NUM_G1_POINTS);
     CHECK(n2 == TRUSTED_SETUP_NUM_G2_POINTS);
 
-    /* 1<<max_scale is the smallest power of 2 >= n1 */
-    uint32_t max_scale = 0;
-    while ((1ULL << max_scale) < n1)
-        max_scale++;
-
-    /* Set the max_width */
-    out->max_width = 1ULL << max_scale;
+    /*
+     * The roots of unity cover the extended domain used by cells, which is
+     * twice the size of a blob. The first half of the bit-reversed roots is
+     * exactly the blob domain.
+     */
+    out->max_width = FIELD_ELEMENTS_PER_EXT_BLOB;
 
     /* Allocate all of our arrays */
     ret = new_fr_array(&out->roots_of_unity, out->max_width);
     if (ret != C_KZG_OK) goto out_error;
+    ret = new_fr_array(&out->expanded_roots_of_unity, out->max_width + 1);
+    if (ret != C_KZG_OK) goto out_error;
+    ret = new_fr_array(&out->reverse_roots_of_unity, out->max_width + 1);
+    if (ret != C_KZG_OK) goto out_error;
     ret = new_g1_array(&out->g1_values, n1);
     if (ret != C_KZG_OK) goto out_error;
+    if (g1_monomial_bytes != NULL) {
+        ret = new_g1_array(&out->g1_values_monomial, n1);
+        if (ret != C_KZG_OK) goto out_error;
+    }
     ret = new_g2_array(&out->g2_values, n2);
     if (ret != C_KZG_OK) goto out_error;
 
     /* Convert all g1 bytes to g1 points */
-    for (uint64_t i = 0; i < n1; i++) {
-        blst_p1_affine g1_affine;
-        BLST_ERROR err = blst_p1_uncompress(
-            &g1_affine, &g1_bytes[BYTES_PER_G1 * i]
+    ret = g1_values_from_bytes(out->g1_values, g1_lagrange_bytes, n1);
+    if (ret != C_KZG_OK) goto out_error;
+    if (g1_monomial_bytes != NULL) {
+        ret = g1_values_from_bytes(
+            out->g1_values_monomial, g1_monomial_bytes, n1
         );
-        if (err != BLST_SUCCESS) {
-            ret = C_KZG_BADARGS;
-            goto out_error;
-        }
-        blst_p1_from_affine(&out->g1_values[i], &g1_affine);
+        if (ret != C_KZG_OK) goto out_error;
     }
 
     /* Convert all g2 bytes to g2 points */
@@ -1738,30 +1814,53 @@ C_KZG_RET load_trusted_setup(
     if (ret != C_KZG_OK) goto out_error;
 
     /* Compute roots of unity and permute the G1 trusted setup */
-    ret = compute_roots_of_unity(out->roots_of_unity, max_scale);
+    ret = compute_roots_of_unity(out);
     if (ret != C_KZG_OK) goto out_error;
     ret = bit_reversal_permutation(out->g1_values, sizeof(g1_t), n1);
     if (ret != C_KZG_OK) goto out_error;
 
+    /* Precompute the FK20 columns used for cell proofs */
+    if (g1_monomial_bytes != NULL) {
+        ret = init_fk20_columns(out);
+        if (ret != C_KZG_OK) goto out_error;
+    }
+
     goto out_success;
 
 out_error:
     /*
-     * Note: this only frees the fields in the KZGSettings structure
-     * (roots_of_unity, g1_values, g2_values). It does not free the KZGSettings
-     * structure memory. If necessary, that must be done by the caller.
+     * Note: this only frees the fields in the KZGSettings structure. It does
+     * not free the KZGSettings structure memory. If necessary, that must be
+     * done by the caller.
      */
     free_trusted_setup(out);
 out_success:
     return ret;
 }
 
+/**
+ * Read `n` hex-encoded bytes from a file.
+ *
+ * @param[out] out The bytes read
+ * @param[in]  n   The number of bytes to read
+ * @param[in]  in  File handle for input
+ */
+static C_KZG_RET read_hex_bytes(uint8_t *out, size_t n, FILE *in) {
+    for (size_t i = 0; i < n; i++) {
+        int num_matches = fscanf(in, "%2hhx", &out[i]);
+        CHECK(num_matches == 1);
+    }
+    return C_KZG_OK;
+}
+
 /**
  * Load trusted setup from a file.
  *
- * @remark The file format is `n1 n2 g1_1 g1_2 ... g1_n1 g2_1 ... g2_n2` where
- *     the first two numbers are in decimal and the remainder are hexstrings
- *     and any whitespace can be used as separators.
+ * @remark The file format is `n1 n2 g1_1 ... g1_n1 g2_1 ... g2_n2 m1_1 ...
+ *     m1_n1` where the first two numbers are in decimal, `g1` are the
+ *     Lagrange form points, `g2` the monomial form points and `m1` the
+ *     monomial form G1 points. The remainder are hexstrings and any
+ *     whitespace can be used as separators.
  *
  * @remark See also load_trusted_setup().
  * @remark The input file will not be closed.
@@ -1770,10 +1869,12 @@ out_success:
  * @param[in]  in  File handle for input
  */
 C_KZG_RET load_trusted_setup_file(KZGSettings *out, FILE *in) {
+    C_KZG_RET ret;
     int num_matches;
     uint64_t i;
-    uint8_t g1_bytes[TRUSTED_SETUP_NUM_G1_POINTS * BYTES_PER_G1];
-    uint8_t g2_bytes[TRUSTED_SETUP_NUM_G2_POINTS * BYTES_PER_G2];
+    uint8_t *g1_monomial_bytes = NULL;
+    uint8_t *g1_lagrange_bytes = NULL;
+    uint8_t *g2_bytes = NULL;
 
     /* Read the number of g1 points */
     num_matches = fscanf(in, "%" SCNu64, &i);
@@ -1785,23 +1886,1176 @@ C_KZG_RET load_trusted_setup_file(KZGSettings *out, FILE *in) {
     CHECK(num_matches == 1);
     CHECK(i == TRUSTED_SETUP_NUM_G2_POINTS);
 
-    /* Read all of the g1 points, byte by byte */
-    for (i = 0; i < TRUSTED_SETUP_NUM_G1_POINTS * BYTES_PER_G1; i++) {
-        num_matches = fscanf(in, "%2hhx", &g1_bytes[i]);
-        CHECK(num_matches == 1);
-    }
+    /* The G1 arrays are too large to comfortably live on the stack */
+    ret = c_kzg_malloc(
+        (void **)&g1_monomial_bytes, TRUSTED_SETUP_NUM_G1_POINTS * BYTES_PER_G1
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = c_kzg_malloc(
+        (void **)&g1_lagrange_bytes, TRUSTED_SETUP_NUM_G1_POINTS * BYTES_PER_G1
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = c_kzg_malloc(
+        (void **)&g2_bytes, TRUSTED_SETUP_NUM_G2_POINTS * BYTES_PER_G2
+    );
+    if (ret != C_KZG_OK) goto out;
 
-    /* Read all of the g2 points, byte by byte */
-    for (i = 0; i < TRUSTED_SETUP_NUM_G2_POINTS * BYTES_PER_G2; i++) {
-        num_matches = fscanf(in, "%2hhx", &g2_bytes[i]);
-        CHECK(num_matches == 1);
-    }
+    /* Read all of the points, byte by byte */
+    ret = read_hex_bytes(
+        g1_lagrange_bytes, TRUSTED_SETUP_NUM_G1_POINTS * BYTES_PER_G1, in
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = read_hex_bytes(
+        g2_bytes, TRUSTED_SETUP_NUM_G2_POINTS * BYTES_PER_G2, in
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = read_hex_bytes(
+        g1_monomial_bytes, TRUSTED_SETUP_NUM_G1_POINTS * BYTES_PER_G1, in
+    );
+    if (ret != C_KZG_OK) goto out;
 
-    return load_trusted_setup(
+    ret = load_trusted_setup(
         out,
-        g1_bytes,
+        g1_monomial_bytes,
+        g1_lagrange_bytes,
         TRUSTED_SETUP_NUM_G1_POINTS,
         g2_bytes,
         TRUSTED_SETUP_NUM_G2_POINTS
     );
-}
\ No newline at end of file
+
+out:
+    c_kzg_free(g1_monomial_bytes);
+    c_kzg_free(g1_lagrange_bytes);
+    c_kzg_free(g2_bytes);
+    return ret;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Fast Fourier Transform Functions
+///////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Recursive radix-2 FFT over field elements.
+ *
+ * @param[out] out          The results, length @p n
+ * @param[in]  in           The input values, read every @p stride elements
+ * @param[in]  stride       The input stride
+ * @param[in]  roots        Powers of a root of unity, in natural order
+ * @param[in]  roots_stride The stride through @p roots for this size
+ * @param[in]  n            The length of the transform, a power of two
+ */
+static void fr_fft_fast(
+    fr_t *out,
+    const fr_t *in,
+    size_t stride,
+    const fr_t *roots,
+    size_t roots_stride,
+    size_t n
+) {
+    size_t half = n / 2;
+    if (half > 0) {
+        fr_t y_times_root;
+        fr_fft_fast(out, in, stride * 2, roots, roots_stride * 2, half);
+        fr_fft_fast(
+            out + half, in + stride, stride * 2, roots, roots_stride * 2, half
+        );
+        for (size_t i = 0; i < half; i++) {
+            blst_fr_mul(&y_times_root, &out[i + half], &roots[i * roots_stride]);
+            blst_fr_sub(&out[i + half], &out[i], &y_times_root);
+            blst_fr_add(&out[i], &out[i], &y_times_root);
+        }
+    } else {
+        *out = *in;
+    }
+}
+
+/**
+ * Compute the FFT of @p in over the size @p n subgroup.
+ *
+ * @param[out] out The evaluations, length @p n
+ * @param[in]  in  The coefficients, length @p n
+ * @param[in]  n   The size, a power of two no larger than `s->max_width`
+ * @param[in]  s   The trusted setup
+ */
+static C_KZG_RET fr_fft(
+    fr_t *out, const fr_t *in, size_t n, const KZGSettings *s
+) {
+    CHECK(n != 0 && n <= s->max_width && is_power_of_two(n));
+    size_t roots_stride = s->max_width / n;
+    fr_fft_fast(out, in, 1, s->expanded_roots_of_unity, roots_stride, n);
+    return C_KZG_OK;
+}
+
+/**
+ * Compute the inverse FFT of @p in over the size @p n subgroup.
+ *
+ * @param[out] out The coefficients, length @p n
+ * @param[in]  in  The evaluations, length @p n
+ * @param[in]  n   The size, a power of two no larger than `s->max_width`
+ * @param[in]  s   The trusted setup
+ */
+static C_KZG_RET fr_ifft(
+    fr_t *out, const fr_t *in, size_t n, const KZGSettings *s
+) {
+    CHECK(n != 0 && n <= s->max_width && is_power_of_two(n));
+    size_t roots_stride = s->max_width / n;
+    fr_fft_fast(out, in, 1, s->reverse_roots_of_unity, roots_stride, n);
+
+    fr_t inv_len;
+    fr_from_uint64(&inv_len, n);
+    blst_fr_inverse(&inv_len, &inv_len);
+    for (size_t i = 0; i < n; i++) {
+        blst_fr_mul(&out[i], &out[i], &inv_len);
+    }
+    return C_KZG_OK;
+}
+
+/**
+ * Multiply the coefficients of a polynomial by successive powers of @p shift,
+ * so that evaluating the result over a subgroup evaluates the original over
+ * the coset `shift * subgroup`.
+ *
+ * @param[in,out] p     The coefficients, length @p n
+ * @param[in]     shift The coset shift
+ * @param[in]     n     The number of coefficients
+ */
+static void shift_poly(fr_t *p, const fr_t *shift, size_t n) {
+    fr_t factor_power = FR_ONE;
+    for (size_t i = 1; i < n; i++) {
+        blst_fr_mul(&factor_power, &factor_power, shift);
+        blst_fr_mul(&p[i], &p[i], &factor_power);
+    }
+}
+
+/**
+ * Evaluate a polynomial over the coset `RECOVERY_SHIFT_FACTOR * subgroup`.
+ *
+ * @param[out] out The evaluations, length @p n
+ * @param[in]  in  The coefficients, length @p n
+ * @param[in]  n   The size of the subgroup
+ * @param[in]  s   The trusted setup
+ */
+static C_KZG_RET coset_fft(
+    fr_t *out, const fr_t *in, size_t n, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    fr_t shift;
+    fr_t *in_shifted = NULL;
+
+    ret = new_fr_array(&in_shifted, n);
+    if (ret != C_KZG_OK) goto out;
+
+    memcpy(in_shifted, in, n * sizeof(fr_t));
+    fr_from_uint64(&shift, RECOVERY_SHIFT_FACTOR);
+    shift_poly(in_shifted, &shift, n);
+
+    ret = fr_fft(out, in_shifted, n, s);
+
+out:
+    c_kzg_free(in_shifted);
+    return ret;
+}
+
+/**
+ * Interpolate a polynomial from its evaluations over the coset
+ * `RECOVERY_SHIFT_FACTOR * subgroup`. The inverse of coset_fft().
+ *
+ * @param[out] out The coefficients, length @p n
+ * @param[in]  in  The evaluations, length @p n
+ * @param[in]  n   The size of the subgroup
+ * @param[in]  s   The trusted setup
+ */
+static C_KZG_RET coset_ifft(
+    fr_t *out, const fr_t *in, size_t n, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    fr_t shift, inv_shift;
+
+    ret = fr_ifft(out, in, n, s);
+    if (ret != C_KZG_OK) return ret;
+
+    fr_from_uint64(&shift, RECOVERY_SHIFT_FACTOR);
+    blst_fr_inverse(&inv_shift, &shift);
+    shift_poly(out, &inv_shift, n);
+
+    return C_KZG_OK;
+}
+
+/**
+ * Recursive radix-2 FFT over G1 points. See fr_fft_fast().
+ *
+ * @param[out] out          The results, length @p n
+ * @param[in]  in           The input points, read every @p stride elements
+ * @param[in]  stride       The input stride
+ * @param[in]  roots        Powers of a root of unity, in natural order
+ * @param[in]  roots_stride The stride through @p roots for this size
+ * @param[in]  n            The length of the transform, a power of two
+ */
+static void g1_fft_fast(
+    g1_t *out,
+    const g1_t *in,
+    size_t stride,
+    const fr_t *roots,
+    size_t roots_stride,
+    size_t n
+) {
+    size_t half = n / 2;
+    if (half > 0) {
+        g1_t y_times_root;
+        g1_fft_fast(out, in, stride * 2, roots, roots_stride * 2, half);
+        g1_fft_fast(
+            out + half, in + stride, stride * 2, roots, roots_stride * 2, half
+        );
+        for (size_t i = 0; i < half; i++) {
+            g1_mul(&y_times_root, &out[i + half], &roots[i * roots_stride]);
+            g1_sub(&out[i + half], &out[i], &y_times_root);
+            blst_p1_add_or_double(&out[i], &out[i], &y_times_root);
+        }
+    } else {
+        *out = *in;
+    }
+}
+
+/**
+ * Compute the FFT of the G1 points @p in over the size @p n subgroup.
+ *
+ * @param[out] out The results, length @p n
+ * @param[in]  in  The input points, length @p n
+ * @param[in]  n   The size, a power of two no larger than `s->max_width`
+ * @param[in]  s   The trusted setup
+ */
+static C_KZG_RET g1_fft(
+    g1_t *out, const g1_t *in, size_t n, const KZGSettings *s
+) {
+    CHECK(n != 0 && n <= s->max_width && is_power_of_two(n));
+    size_t roots_stride = s->max_width / n;
+    g1_fft_fast(out, in, 1, s->expanded_roots_of_unity, roots_stride, n);
+    return C_KZG_OK;
+}
+
+/**
+ * Compute the inverse FFT of the G1 points @p in over the size @p n subgroup.
+ *
+ * @param[out] out The results, length @p n
+ * @param[in]  in  The input points, length @p n
+ * @param[in]  n   The size, a power of two no larger than `s->max_width`
+ * @param[in]  s   The trusted setup
+ */
+static C_KZG_RET g1_ifft(
+    g1_t *out, const g1_t *in, size_t n, const KZGSettings *s
+) {
+    CHECK(n != 0 && n <= s->max_width && is_power_of_two(n));
+    size_t roots_stride = s->max_width / n;
+    g1_fft_fast(out, in, 1, s->reverse_roots_of_unity, roots_stride, n);
+
+    fr_t inv_len;
+    fr_from_uint64(&inv_len, n);
+    blst_fr_inverse(&inv_len, &inv_len);
+    for (size_t i = 0; i < n; i++) {
+        g1_mul(&out[i], &out[i], &inv_len);
+    }
+    return C_KZG_OK;
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Cell Functions (EIP-7594)
+///////////////////////////////////////////////////////////////////////////////
+
+/*
+ * A cell holds FIELD_ELEMENTS_PER_CELL consecutive evaluations of the blob
+ * polynomial over the bit-reversed extended domain. Cell `i` is therefore the
+ * coset `h_i * <w^CELLS_PER_EXT_BLOB>` with `h_i = w^reverse_bits_7(i)`, and
+ * its proof opens the polynomial at all of those points at once. The proofs
+ * for every cell are computed together with the FK20 method: a Toeplitz
+ * matrix-vector product embedded in a circulant of size CELLS_PER_EXT_BLOB.
+ */
+
+/**
+ * Reverse the low `log2(n)` bits of `i`.
+ *
+ * @param[in] i The index to reverse, less than @p n
+ * @param[in] n The domain size, a power of two greater than one
+ */
+static uint32_t reverse_bits_limited(uint32_t i, uint32_t n) {
+    return reverse_bits(i) >> (32 - log2_pow2(n));
+}
+
+/**
+ * Precompute the FFTs of the trusted setup columns used by FK20.
+ *
+ * For each offset `b` in a cell, the column vector
+ * `[tau^b], 0, ..., 0, [tau^((k-2)*l+b)], ..., [tau^(l+b)]`
+ * (with `l = FIELD_ELEMENTS_PER_CELL` and `k = FIELD_ELEMENTS_PER_BLOB / l`)
+ * is transformed over the size `2k` subgroup. The results are stored
+ * row-major in `s->x_ext_fft_columns`, so each row is a contiguous MSM input.
+ *
+ * @param[in,out] s The trusted setup, with roots and monomial points loaded
+ */
+static C_KZG_RET init_fk20_columns(KZGSettings *s) {
+    C_KZG_RET ret;
+    size_t k = FIELD_ELEMENTS_PER_BLOB / FIELD_ELEMENTS_PER_CELL;
+    size_t k2 = 2 * k;
+    g1_t *x = NULL;
+    g1_t *x_ext_fft = NULL;
+
+    ret = new_g1_array(&s->x_ext_fft_columns, k2 * FIELD_ELEMENTS_PER_CELL);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_g1_array(&x, k2);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_g1_array(&x_ext_fft, k2);
+    if (ret != C_KZG_OK) goto out;
+
+    for (size_t b = 0; b < FIELD_ELEMENTS_PER_CELL; b++) {
+        for (size_t i = 0; i < k2; i++) {
+            x[i] = G1_IDENTITY;
+        }
+        x[0] = s->g1_values_monomial[b];
+        for (size_t u = 1; u + 1 < k; u++) {
+            x[k2 - u] = s->g1_values_monomial[u * FIELD_ELEMENTS_PER_CELL + b];
+        }
+
+        ret = g1_fft(x_ext_fft, x, k2, s);
+        if (ret != C_KZG_OK) goto out;
+
+        for (size_t i = 0; i < k2; i++) {
+            s->x_ext_fft_columns[i * FIELD_ELEMENTS_PER_CELL + b] =
+                x_ext_fft[i];
+        }
+    }
+
+out:
+    c_kzg_free(x);
+    c_kzg_free(x_ext_fft);
+    return ret;
+}
+
+/**
+ * Compute the proofs for all cells of a polynomial with the FK20 method.
+ *
+ * @param[out] out  The proofs, in cell order, length CELLS_PER_EXT_BLOB
+ * @param[in]  poly The polynomial coefficients, length FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  s    The trusted setup
+ */
+static C_KZG_RET compute_fk20_proofs(
+    g1_t *out, const fr_t *poly, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    size_t k = FIELD_ELEMENTS_PER_BLOB / FIELD_ELEMENTS_PER_CELL;
+    size_t k2 = 2 * k;
+    fr_t *circulant = NULL;
+    fr_t *circulant_fft = NULL;
+    fr_t *toeplitz_coeffs_fft = NULL;
+    g1_t *h_ext_fft = NULL;
+    g1_t *h = NULL;
+
+    ret = new_fr_array(&circulant, k2);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&circulant_fft, k2);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&toeplitz_coeffs_fft, k2 * FIELD_ELEMENTS_PER_CELL);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_g1_array(&h_ext_fft, k2);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_g1_array(&h, k2);
+    if (ret != C_KZG_OK) goto out;
+
+    /* Transform the Toeplitz coefficients for each offset in a cell */
+    for (size_t b = 0; b < FIELD_ELEMENTS_PER_CELL; b++) {
+        for (size_t m = 0; m < k2; m++) {
+            if (m + 1 < k) {
+                circulant[m] = poly[(m + 1) * FIELD_ELEMENTS_PER_CELL + b];
+            } else {
+                circulant[m] = FR_ZERO;
+            }
+        }
+        ret = fr_fft(circulant_fft, circulant, k2, s);
+        if (ret != C_KZG_OK) goto out;
+        for (size_t i = 0; i < k2; i++) {
+            toeplitz_coeffs_fft[i * FIELD_ELEMENTS_PER_CELL + b] =
+                circulant_fft[i];
+        }
+    }
+
+    /* Pointwise products in the transformed domain, summed over offsets */
+    for (size_t i = 0; i < k2; i++) {
+        ret = g1_lincomb_fast(
+            &h_ext_fft[i],
+            &s->x_ext_fft_columns[i * FIELD_ELEMENTS_PER_CELL],
+            &toeplitz_coeffs_fft[i * FIELD_ELEMENTS_PER_CELL],
+            FIELD_ELEMENTS_PER_CELL
+        );
+        if (ret != C_KZG_OK) goto out;
+    }
+
+    /* Only the first k - 1 entries of the circulant product are wanted */
+    ret = g1_ifft(h, h_ext_fft, k2, s);
+    if (ret != C_KZG_OK) goto out;
+    for (size_t i = k - 1; i < k2; i++) {
+        h[i] = G1_IDENTITY;
+    }
+
+    /* Evaluate over the cosets, then put them in cell order */
+    ret = g1_fft(out, h, k2, s);
+    if (ret != C_KZG_OK) goto out;
+    ret = bit_reversal_permutation(out, sizeof(g1_t), k2);
+
+out:
+    c_kzg_free(circulant);
+    c_kzg_free(circulant_fft);
+    c_kzg_free(toeplitz_coeffs_fft);
+    c_kzg_free(h_ext_fft);
+    c_kzg_free(h);
+    return ret;
+}
+
+/**
+ * Compute the cells and cell proofs of a polynomial in monomial form.
+ *
+ * @remark Either of @p cells and @p proofs may be NULL to skip computing it.
+ *
+ * @param[out] cells  The cells, length CELLS_PER_EXT_BLOB
+ * @param[out] proofs The cell proofs, length CELLS_PER_EXT_BLOB
+ * @param[in]  poly   The coefficients, length FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  s      The trusted setup
+ */
+static C_KZG_RET compute_cells_and_kzg_proofs_impl(
+    Cell *cells, KZGProof *proofs, const fr_t *poly, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    fr_t *poly_ext = NULL;
+    fr_t *data = NULL;
+    g1_t *proofs_g1 = NULL;
+
+    if (cells != NULL) {
+        ret = new_fr_array(&poly_ext, FIELD_ELEMENTS_PER_EXT_BLOB);
+        if (ret != C_KZG_OK) goto out;
+        ret = new_fr_array(&data, FIELD_ELEMENTS_PER_EXT_BLOB);
+        if (ret != C_KZG_OK) goto out;
+
+        /* Evaluate over the extended domain, in bit-reversed order */
+        for (size_t i = 0; i < FIELD_ELEMENTS_PER_EXT_BLOB; i++) {
+            poly_ext[i] = i < FIELD_ELEMENTS_PER_BLOB ? poly[i] : FR_ZERO;
+        }
+        ret = fr_fft(data, poly_ext, FIELD_ELEMENTS_PER_EXT_BLOB, s);
+        if (ret != C_KZG_OK) goto out;
+        ret = bit_reversal_permutation(
+            data, sizeof(fr_t), FIELD_ELEMENTS_PER_EXT_BLOB
+        );
+        if (ret != C_KZG_OK) goto out;
+
+        for (size_t i = 0; i < CELLS_PER_EXT_BLOB; i++) {
+            for (size_t j = 0; j < FIELD_ELEMENTS_PER_CELL; j++) {
+                size_t index = i * FIELD_ELEMENTS_PER_CELL + j;
+                bytes_from_bls_field(
+                    (Bytes32 *)&cells[i].bytes[j * BYTES_PER_FIELD_ELEMENT],
+                    &data[index]
+                );
+            }
+        }
+    }
+
+    if (proofs != NULL) {
+        ret = new_g1_array(&proofs_g1, CELLS_PER_EXT_BLOB);
+        if (ret != C_KZG_OK) goto out;
+        ret = compute_fk20_proofs(proofs_g1, poly, s);
+        if (ret != C_KZG_OK) goto out;
+        for (size_t i = 0; i < CELLS_PER_EXT_BLOB; i++) {
+            bytes_from_g1(&proofs[i], &proofs_g1[i]);
+        }
+    }
+
+    ret = C_KZG_OK;
+
+out:
+    c_kzg_free(poly_ext);
+    c_kzg_free(data);
+    c_kzg_free(proofs_g1);
+    return ret;
+}
+
+/**
+ * Compute all the cells of a blob and their proofs.
+ *
+ * @remark Either of @p cells and @p proofs may be NULL to skip computing it.
+ *
+ * @param[out] cells  An array of CELLS_PER_EXT_BLOB cells
+ * @param[out] proofs An array of CELLS_PER_EXT_BLOB proofs
+ * @param[in]  blob   The blob to compute cells for
+ * @param[in]  s      The trusted setup
+ */
+C_KZG_RET compute_cells_and_kzg_proofs(
+    Cell *cells, KZGProof *proofs, const Blob *blob, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    Polynomial polynomial;
+    fr_t *poly = NULL;
+
+    /* Cells need the monomial form points */
+    CHECK(s->g1_values_monomial != NULL);
+
+    ret = new_fr_array(&poly, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+
+    /* The blob holds evaluations in bit-reversed order; get coefficients */
+    ret = blob_to_polynomial(&polynomial, blob);
+    if (ret != C_KZG_OK) goto out;
+    ret = bit_reversal_permutation(
+        polynomial.evals, sizeof(fr_t), FIELD_ELEMENTS_PER_BLOB
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_ifft(poly, polynomial.evals, FIELD_ELEMENTS_PER_BLOB, s);
+    if (ret != C_KZG_OK) goto out;
+
+    ret = compute_cells_and_kzg_proofs_impl(cells, proofs, poly, s);
+
+out:
+    c_kzg_free(poly);
+    return ret;
+}
+
+/**
+ * Convert a blob from evaluation form to coefficient form.
+ *
+ * @remark The coefficients are written in natural order, one field element
+ *     per 32 bytes, so the output is itself a valid blob.
+ *
+ * @param[out] out  The coefficients of the blob's polynomial
+ * @param[in]  blob The blob, evaluations in bit-reversed order
+ * @param[in]  s    The trusted setup
+ */
+C_KZG_RET blob_to_coefficients(
+    Blob *out, const Blob *blob, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    Polynomial polynomial;
+    fr_t *poly = NULL;
+
+    ret = new_fr_array(&poly, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+
+    ret = blob_to_polynomial(&polynomial, blob);
+    if (ret != C_KZG_OK) goto out;
+    ret = bit_reversal_permutation(
+        polynomial.evals, sizeof(fr_t), FIELD_ELEMENTS_PER_BLOB
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_ifft(poly, polynomial.evals, FIELD_ELEMENTS_PER_BLOB, s);
+    if (ret != C_KZG_OK) goto out;
+
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
+        bytes_from_bls_field(
+            (Bytes32 *)&out->bytes[i * BYTES_PER_FIELD_ELEMENT], &poly[i]
+        );
+    }
+
+out:
+    c_kzg_free(poly);
+    return ret;
+}
+
+/**
+ * Convert a polynomial in coefficient form to a blob.
+ *
+ * @param[out] out    The blob, evaluations in bit-reversed order
+ * @param[in]  coeffs The coefficients in natural order, as produced by
+ *                    blob_to_coefficients()
+ * @param[in]  s      The trusted setup
+ */
+C_KZG_RET coefficients_to_blob(
+    Blob *out, const Blob *coeffs, const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    Polynomial polynomial;
+    fr_t *evals = NULL;
+
+    ret = new_fr_array(&evals, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+
+    ret = blob_to_polynomial(&polynomial, coeffs);
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_fft(evals, polynomial.evals, FIELD_ELEMENTS_PER_BLOB, s);
+    if (ret != C_KZG_OK) goto out;
+    ret = bit_reversal_permutation(
+        evals, sizeof(fr_t), FIELD_ELEMENTS_PER_BLOB
+    );
+    if (ret != C_KZG_OK) goto out;
+
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_BLOB; i++) {
+        bytes_from_bls_field(
+            (Bytes32 *)&out->bytes[i * BYTES_PER_FIELD_ELEMENT], &evals[i]
+        );
+    }
+
+out:
+    c_kzg_free(evals);
+    return ret;
+}
+
+/**
+ * Deserialize the field elements of a cell, checking they are canonical.
+ *
+ * @param[out] out  The field elements, length FIELD_ELEMENTS_PER_CELL
+ * @param[in]  cell The cell to deserialize
+ */
+static C_KZG_RET cell_to_fr_array(fr_t *out, const Cell *cell) {
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_CELL; i++) {
+        C_KZG_RET ret = bytes_to_bls_field(
+            &out[i],
+            (const Bytes32 *)&cell->bytes[i * BYTES_PER_FIELD_ELEMENT]
+        );
+        if (ret != C_KZG_OK) return ret;
+    }
+    return C_KZG_OK;
+}
+
+/**
+ * Compute the vanishing polynomial of the cells missing from @p cell_indices.
+ *
+ * The result is `prod (X^l - h_i^l)` over the missing cells `i`, where
+ * `l = FIELD_ELEMENTS_PER_CELL`, and so vanishes on every point of those
+ * cells.
+ *
+ * @param[out] out          The coefficients, length FIELD_ELEMENTS_PER_EXT_BLOB
+ * @param[in]  cell_indices The indices of the available cells
+ * @param[in]  num_cells    The number of available cells
+ * @param[in]  s            The trusted setup
+ */
+static C_KZG_RET vanishing_polynomial_for_missing_cells(
+    fr_t *out,
+    const uint64_t *cell_indices,
+    size_t num_cells,
+    const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    bool present[CELLS_PER_EXT_BLOB] = {false};
+    fr_t *short_poly = NULL;
+    size_t len = 1;
+
+    ret = new_fr_array(&short_poly, CELLS_PER_EXT_BLOB + 1);
+    if (ret != C_KZG_OK) goto out;
+
+    for (size_t i = 0; i < num_cells; i++) {
+        present[cell_indices[i]] = true;
+    }
+
+    /* Multiply out prod (X - h_i^l) one missing cell at a time */
+    short_poly[0] = FR_ONE;
+    for (uint32_t i = 0; i < CELLS_PER_EXT_BLOB; i++) {
+        if (present[i]) continue;
+        uint32_t rev = reverse_bits_limited(i, CELLS_PER_EXT_BLOB);
+        const fr_t *root =
+            &s->expanded_roots_of_unity[rev * FIELD_ELEMENTS_PER_CELL];
+        short_poly[len] = short_poly[len - 1];
+        for (size_t j = len - 1; j > 0; j--) {
+            fr_t tmp;
+            blst_fr_mul(&tmp, &short_poly[j], root);
+            blst_fr_sub(&short_poly[j], &short_poly[j - 1], &tmp);
+        }
+        blst_fr_mul(&short_poly[0], &short_poly[0], root);
+        blst_fr_cneg(&short_poly[0], &short_poly[0], true);
+        len++;
+    }
+
+    /* Substitute X^l for X */
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_EXT_BLOB; i++) {
+        out[i] = FR_ZERO;
+    }
+    for (size_t i = 0; i < len; i++) {
+        out[i * FIELD_ELEMENTS_PER_CELL] = short_poly[i];
+    }
+
+out:
+    c_kzg_free(short_poly);
+    return ret;
+}
+
+/**
+ * Recover the polynomial coefficients from at least half of the cells.
+ *
+ * @param[out] out          The coefficients, length FIELD_ELEMENTS_PER_BLOB
+ * @param[in]  cell_indices The indices of the available cells
+ * @param[in]  cells        The available cells
+ * @param[in]  num_cells    The number of available cells
+ * @param[in]  s            The trusted setup
+ */
+static C_KZG_RET recover_polynomial_coeff(
+    fr_t *out,
+    const uint64_t *cell_indices,
+    const Cell *cells,
+    size_t num_cells,
+    const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    fr_t *ext_evals = NULL;
+    fr_t *zero_poly = NULL;
+    fr_t *zero_poly_eval = NULL;
+    fr_t *product = NULL;
+    fr_t *product_coeff = NULL;
+    fr_t *coset_product = NULL;
+    fr_t *coset_zero_poly = NULL;
+
+    ret = new_fr_array(&ext_evals, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&zero_poly, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&zero_poly_eval, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&product, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&product_coeff, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&coset_product, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&coset_zero_poly, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+
+    /* Gather the known evaluations, with zeros for missing cells */
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_EXT_BLOB; i++) {
+        ext_evals[i] = FR_ZERO;
+    }
+    for (size_t i = 0; i < num_cells; i++) {
+        size_t index = cell_indices[i] * FIELD_ELEMENTS_PER_CELL;
+        ret = cell_to_fr_array(&ext_evals[index], &cells[i]);
+        if (ret != C_KZG_OK) goto out;
+    }
+    ret = bit_reversal_permutation(
+        ext_evals, sizeof(fr_t), FIELD_ELEMENTS_PER_EXT_BLOB
+    );
+    if (ret != C_KZG_OK) goto out;
+
+    /*
+     * With Z vanishing on the missing points, E * Z agrees with P * Z on the
+     * whole domain, and P * Z has degree below the domain size.
+     */
+    ret = vanishing_polynomial_for_missing_cells(
+        zero_poly, cell_indices, num_cells, s
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_fft(zero_poly_eval, zero_poly, FIELD_ELEMENTS_PER_EXT_BLOB, s);
+    if (ret != C_KZG_OK) goto out;
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_EXT_BLOB; i++) {
+        blst_fr_mul(&product[i], &ext_evals[i], &zero_poly_eval[i]);
+    }
+    ret = fr_ifft(product_coeff, product, FIELD_ELEMENTS_PER_EXT_BLOB, s);
+    if (ret != C_KZG_OK) goto out;
+
+    /* Divide by Z over a coset, where Z has no roots */
+    ret = coset_fft(
+        coset_product, product_coeff, FIELD_ELEMENTS_PER_EXT_BLOB, s
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = coset_fft(coset_zero_poly, zero_poly, FIELD_ELEMENTS_PER_EXT_BLOB, s);
+    if (ret != C_KZG_OK) goto out;
+    ret = fr_batch_inv(
+        zero_poly_eval, coset_zero_poly, FIELD_ELEMENTS_PER_EXT_BLOB
+    );
+    if (ret != C_KZG_OK) goto out;
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_EXT_BLOB; i++) {
+        blst_fr_mul(&coset_product[i], &coset_product[i], &zero_poly_eval[i]);
+    }
+    ret = coset_ifft(
+        product_coeff, coset_product, FIELD_ELEMENTS_PER_EXT_BLOB, s
+    );
+    if (ret != C_KZG_OK) goto out;
+
+    memcpy(out, product_coeff, FIELD_ELEMENTS_PER_BLOB * sizeof(fr_t));
+
+out:
+    c_kzg_free(ext_evals);
+    c_kzg_free(zero_poly);
+    c_kzg_free(zero_poly_eval);
+    c_kzg_free(product);
+    c_kzg_free(product_coeff);
+    c_kzg_free(coset_product);
+    c_kzg_free(coset_zero_poly);
+    return ret;
+}
+
+/**
+ * Given at least half of the cells of a blob, recover all of the cells and
+ * their proofs.
+ *
+ * @remark The cell indices must be unique, but may be in any order.
+ *
+ * @param[out] recovered_cells  An array of CELLS_PER_EXT_BLOB cells
+ * @param[out] recovered_proofs An array of CELLS_PER_EXT_BLOB proofs
+ * @param[in]  cell_indices     The indices of the available cells
+ * @param[in]  cells            The available cells
+ * @param[in]  num_cells        The number of available cells
+ * @param[in]  s                The trusted setup
+ */
+C_KZG_RET recover_cells_and_kzg_proofs(
+    Cell *recovered_cells,
+    KZGProof *recovered_proofs,
+    const uint64_t *cell_indices,
+    const Cell *cells,
+    size_t num_cells,
+    const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    bool seen[CELLS_PER_EXT_BLOB] = {false};
+    fr_t *poly = NULL;
+
+    /* Cells need the monomial form points */
+    CHECK(s->g1_values_monomial != NULL);
+
+    /* We need at least half of the cells, and no more than all of them */
+    CHECK(num_cells >= CELLS_PER_EXT_BLOB / 2);
+    CHECK(num_cells <= CELLS_PER_EXT_BLOB);
+
+    /* Check the indices are valid and unique */
+    for (size_t i = 0; i < num_cells; i++) {
+        CHECK(cell_indices[i] < CELLS_PER_EXT_BLOB);
+        CHECK(!seen[cell_indices[i]]);
+        seen[cell_indices[i]] = true;
+    }
+
+    ret = new_fr_array(&poly, FIELD_ELEMENTS_PER_BLOB);
+    if (ret != C_KZG_OK) goto out;
+
+    ret = recover_polynomial_coeff(poly, cell_indices, cells, num_cells, s);
+    if (ret != C_KZG_OK) goto out;
+
+    ret = compute_cells_and_kzg_proofs_impl(
+        recovered_cells, recovered_proofs, poly, s
+    );
+
+out:
+    c_kzg_free(poly);
+    return ret;
+}
+
+/**
+ * Compute the random challenge for verify_cell_kzg_proof_batch().
+ *
+ * @param[out] out              The random challenge
+ * @param[in]  commitments      The deduplicated commitments
+ * @param[in]  num_commitments  The number of deduplicated commitments
+ * @param[in]  commitment_indices For each cell, its commitment's index
+ * @param[in]  cell_indices     The index of each cell
+ * @param[in]  cells            The cells
+ * @param[in]  proofs_bytes     The proof of each cell
+ * @param[in]  num_cells        The number of cells
+ */
+static C_KZG_RET compute_cell_batch_challenge(
+    fr_t *out,
+    const Bytes48 *commitments,
+    size_t num_commitments,
+    const uint64_t *commitment_indices,
+    const uint64_t *cell_indices,
+    const Cell *cells,
+    const Bytes48 *proofs_bytes,
+    size_t num_cells
+) {
+    C_KZG_RET ret;
+    Bytes32 hash;
+    uint8_t *bytes = NULL;
+
+    size_t input_size = DOMAIN_STR_LENGTH + 4 * sizeof(uint64_t) +
+                        num_commitments * BYTES_PER_COMMITMENT +
+                        num_cells * (2 * sizeof(uint64_t) + BYTES_PER_CELL +
+                                     BYTES_PER_PROOF);
+    ret = c_kzg_malloc((void **)&bytes, input_size);
+    if (ret != C_KZG_OK) goto out;
+
+    /* Pointer tracking `bytes` for writing on top of it */
+    uint8_t *offset = bytes;
+
+    /* Copy domain separator */
+    memcpy(offset, RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN, DOMAIN_STR_LENGTH);
+    offset += DOMAIN_STR_LENGTH;
+
+    /* Copy the sizes */
+    bytes_from_uint64(offset, FIELD_ELEMENTS_PER_BLOB);
+    offset += sizeof(uint64_t);
+    bytes_from_uint64(offset, FIELD_ELEMENTS_PER_CELL);
+    offset += sizeof(uint64_t);
+    bytes_from_uint64(offset, num_commitments);
+    offset += sizeof(uint64_t);
+    bytes_from_uint64(offset, num_cells);
+    offset += sizeof(uint64_t);
+
+    /* Copy the commitments */
+    for (size_t i = 0; i < num_commitments; i++) {
+        memcpy(offset, &commitments[i], BYTES_PER_COMMITMENT);
+        offset += BYTES_PER_COMMITMENT;
+    }
+
+    /* Copy each cell with its indices and proof */
+    for (size_t i = 0; i < num_cells; i++) {
+        bytes_from_uint64(offset, commitment_indices[i]);
+        offset += sizeof(uint64_t);
+        bytes_from_uint64(offset, cell_indices[i]);
+        offset += sizeof(uint64_t);
+        memcpy(offset, &cells[i], BYTES_PER_CELL);
+        offset += BYTES_PER_CELL;
+        memcpy(offset, &proofs_bytes[i], BYTES_PER_PROOF);
+        offset += BYTES_PER_PROOF;
+    }
+
+    /* Now let's create the challenge! */
+    blst_sha256(hash.bytes, bytes, input_size);
+    hash_to_bls_field(out, &hash);
+
+out:
+    c_kzg_free(bytes);
+    return ret;
+}
+
+/**
+ * Compute `[I(tau)]`, where `I` is the random linear combination of the
+ * interpolation polynomials of every cell.
+ *
+ * Each cell's evaluations are interpolated over its coset. Cells sharing an
+ * index share a coset, so their weighted evaluations are summed first.
+ *
+ * @param[out] out          The commitment to the combined interpolation
+ * @param[in]  cell_indices The index of each cell
+ * @param[in]  cells_fr     The evaluations of each cell
+ * @param[in]  r_powers     The weight of each cell
+ * @param[in]  num_cells    The number of cells
+ * @param[in]  s            The trusted setup
+ */
+static C_KZG_RET compute_commitment_to_aggregated_interpolation_poly(
+    g1_t *out,
+    const uint64_t *cell_indices,
+    const fr_t *cells_fr,
+    const fr_t *r_powers,
+    size_t num_cells,
+    const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    bool used[CELLS_PER_EXT_BLOB] = {false};
+    fr_t *aggregated = NULL;
+    fr_t *column_coeffs = NULL;
+    fr_t *interpolation = NULL;
+
+    ret = new_fr_array(&aggregated, FIELD_ELEMENTS_PER_EXT_BLOB);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&column_coeffs, FIELD_ELEMENTS_PER_CELL);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&interpolation, FIELD_ELEMENTS_PER_CELL);
+    if (ret != C_KZG_OK) goto out;
+
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_EXT_BLOB; i++) {
+        aggregated[i] = FR_ZERO;
+    }
+    for (size_t i = 0; i < FIELD_ELEMENTS_PER_CELL; i++) {
+        interpolation[i] = FR_ZERO;
+    }
+
+    /* Sum the weighted evaluations of cells sharing an index */
+    for (size_t i = 0; i < num_cells; i++) {
+        fr_t *column = &aggregated[cell_indices[i] * FIELD_ELEMENTS_PER_CELL];
+        for (size_t j = 0; j < FIELD_ELEMENTS_PER_CELL; j++) {
+            fr_t tmp;
+            blst_fr_mul(
+                &tmp, &cells_fr[i * FIELD_ELEMENTS_PER_CELL + j], &r_powers[i]
+            );
+            blst_fr_add(&column[j], &column[j], &tmp);
+        }
+        used[cell_indices[i]] = true;
+    }
+
+    for (uint32_t i = 0; i < CELLS_PER_EXT_BLOB; i++) {
+        if (!used[i]) continue;
+        fr_t *column = &aggregated[i * FIELD_ELEMENTS_PER_CELL];
+
+        /* Interpolate over the subgroup, as if the coset shift were one */
+        ret = bit_reversal_permutation(
+            column, sizeof(fr_t), FIELD_ELEMENTS_PER_CELL
+        );
+        if (ret != C_KZG_OK) goto out;
+        ret = fr_ifft(column_coeffs, column, FIELD_ELEMENTS_PER_CELL, s);
+        if (ret != C_KZG_OK) goto out;
+
+        /* Then undo the shift: coefficient d is scaled by h^-d */
+        uint32_t rev = reverse_bits_limited(i, CELLS_PER_EXT_BLOB);
+        shift_poly(
+            column_coeffs,
+            &s->reverse_roots_of_unity[rev],
+            FIELD_ELEMENTS_PER_CELL
+        );
+        for (size_t j = 0; j < FIELD_ELEMENTS_PER_CELL; j++) {
+            blst_fr_add(&interpolation[j], &interpolation[j], &column_coeffs[j]);
+        }
+    }
+
+    g1_lincomb_naive(
+        out, s->g1_values_monomial, interpolation, FIELD_ELEMENTS_PER_CELL
+    );
+
+out:
+    c_kzg_free(aggregated);
+    c_kzg_free(column_coeffs);
+    c_kzg_free(interpolation);
+    return ret;
+}
+
+/**
+ * Given some cells, verify that their proofs are valid.
+ *
+ * @remark The @p commitments_bytes, @p cell_indices, @p cells and
+ *     @p proofs_bytes arrays all have @p num_cells entries; the same
+ *     commitment may appear several times.
+ *
+ * @param[out] ok                True if the proofs are valid
+ * @param[in]  commitments_bytes The commitment for each cell
+ * @param[in]  cell_indices      The index of each cell
+ * @param[in]  cells             The cells
+ * @param[in]  proofs_bytes      The proof of each cell
+ * @param[in]  num_cells         The number of cells
+ * @param[in]  s                 The trusted setup
+ */
+C_KZG_RET verify_cell_kzg_proof_batch(
+    bool *ok,
+    const Bytes48 *commitments_bytes,
+    const uint64_t *cell_indices,
+    const Cell *cells,
+    const Bytes48 *proofs_bytes,
+    size_t num_cells,
+    const KZGSettings *s
+) {
+    C_KZG_RET ret;
+    g1_t proof_lincomb, final_g1_sum, rlc, rli, rlp;
+    fr_t r;
+    Bytes48 *unique_commitments = NULL;
+    uint64_t *commitment_indices = NULL;
+    g1_t *commitments_g1 = NULL;
+    g1_t *proofs_g1 = NULL;
+    fr_t *cells_fr = NULL;
+    fr_t *r_powers = NULL;
+    fr_t *weights = NULL;
+    size_t num_commitments = 0;
+
+    *ok = false;
+
+    /* Cells need the monomial form points */
+    CHECK(s->g1_values_monomial != NULL);
+
+    /* Exit early if we are given zero cells */
+    if (num_cells == 0) {
+        *ok = true;
+        return C_KZG_OK;
+    }
+
+    for (size_t i = 0; i < num_cells; i++) {
+        CHECK(cell_indices[i] < CELLS_PER_EXT_BLOB);
+    }
+
+    ret = c_kzg_calloc(
+        (void **)&unique_commitments, num_cells, sizeof(Bytes48)
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = c_kzg_calloc(
+        (void **)&commitment_indices, num_cells, sizeof(uint64_t)
+    );
+    if (ret != C_KZG_OK) goto out;
+    ret = new_g1_array(&commitments_g1, num_cells);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_g1_array(&proofs_g1, num_cells);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&cells_fr, num_cells * FIELD_ELEMENTS_PER_CELL);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&r_powers, num_cells);
+    if (ret != C_KZG_OK) goto out;
+    ret = new_fr_array(&weights, num_cells);
+    if (ret != C_KZG_OK) goto out;
+
+    /* Deduplicate the commitments, keeping the order of first appearance */
+    for (size_t i = 0; i < num_cells; i++) {
+        size_t j;
+        for (j = 0; j < num_commitments; j++) {
+            if (memcmp(
+                    &unique_commitments[j],
+                    &commitments_bytes[i],
+                    BYTES_PER_COMMITMENT
+                ) == 0) {
+                break;
+            }
+        }
+        if (j == num_commitments) {
+            unique_commitments[num_commitments++] = commitments_bytes[i];
+        }
+        commitment_indices[i] = j;
+    }
+
+    /* Check the inputs are well-formed */
+    for (size_t i = 0; i < num_commitments; i++) {
+        ret = bytes_to_kzg_commitment(
+            &commitments_g1[i], &unique_commitments[i]
+        );
+        if (ret != C_KZG_OK) goto out;
+    }
+    for (size_t i = 0; i < num_cells; i++) {
+        ret = bytes_to_kzg_proof(&proofs_g1[i], &proofs_bytes[i]);
+        if (ret != C_KZG_OK) goto out;
+        ret = cell_to_fr_array(&cells_fr[i * FIELD_ELEMENTS_PER_CELL], &cells[i]);
+        if (ret != C_KZG_OK) goto out;
+    }
+
+    ret = compute_cell_batch_challenge(
+        &r,
+        unique_commitments,
+        num_commitments,
+        commitment_indices,
+        cell_indices,
+        cells,
+        proofs_bytes,
+        num_cells
+    );
+    if (ret != C_KZG_OK) goto out;
+    compute_powers(r_powers, &r, num_cells);
+
+    /* Compute sum(r^k * proof_k) */
+    g1_lincomb_naive(&proof_lincomb, proofs_g1, r_powers, num_cells);
+
+    /* Compute sum(weight_i * commitment_i), summing r^k per commitment */
+    for (size_t i = 0; i < num_commitments; i++) {
+        weights[i] = FR_ZERO;
+    }
+    for (size_t i = 0; i < num_cells; i++) {
+        fr_t *weight = &weights[commitment_indices[i]];
+        blst_fr_add(weight, weight, &r_powers[i]);
+    }
+    g1_lincomb_naive(&rlc, commitments_g1, weights, num_commitments);
+
+    /* Compute the commitment to the combined interpolation polynomial */
+    ret = compute_commitment_to_aggregated_interpolation_poly(
+        &rli, cell_indices, cells_fr, r_powers, num_cells, s
+    );
+    if (ret != C_KZG_OK) goto out;
+
+    /* Compute sum(r^k * h_k^l * proof_k), reusing the weights array */
+    for (size_t i = 0; i < num_cells; i++) {
+        uint32_t rev = reverse_bits_limited(
+            (uint32_t)cell_indices[i], CELLS_PER_EXT_BLOB
+        );
+        const fr_t *h_pow =
+            &s->expanded_roots_of_unity[rev * FIELD_ELEMENTS_PER_CELL];
+        blst_fr_mul(&weights[i], &r_powers[i], h_pow);
+    }
+    g1_lincomb_naive(&rlp, proofs_g1, weights, num_cells);
+
+    /* Put it all together: rlc - rli + rlp */
+    g1_sub(&final_g1_sum, &rlc, &rli);
+    blst_p1_add_or_double(&final_g1_sum, &final_g1_sum, &rlp);
+
+    /* Check e(proof_lincomb, [tau^l]) == e(final_g1_sum, [1]) */
+    *ok = pairings_verify(
+        &proof_lincomb,
+        &s->g2_values[FIELD_ELEMENTS_PER_CELL],
+        &final_g1_sum,
+        blst_p2_generator()
+    );
+
+out:
+    c_kzg_free(unique_commitments);
+    c_kzg_free(commitment_indices);
+    c_kzg_free(commitments_g1);
+    c_kzg_free(proofs_g1);
+    c_kzg_free(cells_fr);
+    c_kzg_free(r_powers);
+    c_kzg_free(weights);
+    return ret;
+}
@@ -17,7 +17,7 @@
 /**
  * @file c_kzg_4844.h
  *
- * Minimal interface required for EIP-4844.
+ * Minimal interface required for EIP-4844 and EIP-7594.
  */
 #ifndef C_KZG_4844_H
 #define C_KZG_4844_H
@@ -45,12 +45,39 @@ extern "C" {
 /** The number of bytes in a BLS scalar field element. */
 #define BYTES_PER_FIELD_ELEMENT 32
 
-/** The number of field elements in a blob. */
+/**
+ * The number of field elements in a blob. This is 4096 in the mainnet preset;
+ * the minimal preset is built with -DFIELD_ELEMENTS_PER_BLOB=4.
+ */
+#ifndef FIELD_ELEMENTS_PER_BLOB
 #define FIELD_ELEMENTS_PER_BLOB 4096
+#endif
 
 /** The number of bytes in a blob. */
 #define BYTES_PER_BLOB (FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT)
 
+/** The number of field elements in an extended blob (EIP-7594). */
+#define FIELD_ELEMENTS_PER_EXT_BLOB (FIELD_ELEMENTS_PER_BLOB * 2)
+
+/**
+ * The number of field elements in a cell. The consensus specs define cells
+ * (EIP-7594) with 64 field elements only, which a minimal preset blob of 4
+ * cannot hold, so smaller blobs are cut into halves instead. That keeps the
+ * cell size a power of two dividing the blob, and leaves 4 cells per extended
+ * blob so that recovery from half of them is still exercised.
+ */
+#if FIELD_ELEMENTS_PER_BLOB >= 64
+#define FIELD_ELEMENTS_PER_CELL 64
+#else
+#define FIELD_ELEMENTS_PER_CELL (FIELD_ELEMENTS_PER_BLOB / 2)
+#endif
+
+/** The number of cells in an extended blob. */
+#define CELLS_PER_EXT_BLOB (FIELD_ELEMENTS_PER_EXT_BLOB / FIELD_ELEMENTS_PER_CELL)
+
+/** The number of bytes in a cell. */
+#define BYTES_PER_CELL (FIELD_ELEMENTS_PER_CELL * BYTES_PER_FIELD_ELEMENT)
+
 ///////////////////////////////////////////////////////////////////////////////
 // Types
 ///////////////////////////////////////////////////////////////////////////////
@@ -82,6 +109,14 @@ typedef struct {
     uint8_t bytes[BYTES_PER_BLOB];
 } Blob;
 
+/**
+ * A cell: a contiguous slice of the extended blob in bit-reversal order,
+ * i.e. the evaluations of the blob polynomial over one coset.
+ */
+typedef struct {
+    uint8_t bytes[BYTES_PER_CELL];
+} Cell;
+
 /**
  * A trusted (valid) KZG commitment.
  */
@@ -110,13 +145,24 @@ typedef struct {
     uint64_t max_width;
     /** Powers of the primitive root of unity determined by
      * `SCALE2_ROOT_OF_UNITY` in bit-reversal permutation order,
-     * length `max_width`. */
+     * length `max_width`. The first `FIELD_ELEMENTS_PER_BLOB` of them are
+     * the blob domain. */
     fr_t *roots_of_unity;
+    /** The same powers in natural order, length `max_width + 1`. */
+    fr_t *expanded_roots_of_unity;
+    /** The inverse powers in natural order, length `max_width + 1`. */
+    fr_t *reverse_roots_of_unity;
     /** G1 group elements from the trusted setup,
      * in Lagrange form bit-reversal permutation. */
     g1_t *g1_values;
+    /** G1 group elements from the trusted setup, in monomial form, or NULL
+     * if the setup was loaded without them. */
+    g1_t *g1_values_monomial;
     /** G2 group elements from the trusted setup. */
     g2_t *g2_values;
+    /** FK20 precomputation for cell proofs: `CELLS_PER_EXT_BLOB` rows of
+     * `FIELD_ELEMENTS_PER_CELL` points, or NULL without monomial points. */
+    g1_t *x_ext_fft_columns;
 } KZGSettings;
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -125,7 +171,8 @@ typedef struct {
 
 C_KZG_RET load_trusted_setup(
     KZGSettings *out,
-    const uint8_t *g1_bytes, /* n1 * 48 bytes */
+    const uint8_t *g1_monomial_bytes, /* n1 * 48 bytes, or NULL */
+    const uint8_t *g1_lagrange_bytes, /* n1 * 48 bytes */
     size_t n1,
     const uint8_t *g2_bytes, /* n2 * 96 bytes */
     size_t n2
@@ -147,6 +194,13 @@ C_KZG_RET compute_kzg_proof(
     const KZGSettings *s
 );
 
+C_KZG_RET evaluate_blob_at(
+    Bytes32 *y_out,
+    const Blob *blob,
+    const Bytes32 *z_bytes,
+    const KZGSettings *s
+);
+
 C_KZG_RET compute_blob_kzg_proof(
     KZGProof *out,
     const Blob *blob,
@@ -180,6 +234,37 @@ C_KZG_RET verify_blob_kzg_proof_batch(
     const KZGSettings *s
 );
 
+C_KZG_RET compute_cells_and_kzg_proofs(
+    Cell *cells, KZGProof *proofs, const Blob *blob, const KZGSettings *s
+);
+
+C_KZG_RET blob_to_coefficients(
+    Blob *out, const Blob *blob, const KZGSettings *s
+);
+
+C_KZG_RET coefficients_to_blob(
+    Blob *out, const Blob *coeffs, const KZGSettings *s
+);
+
+C_KZG_RET recover_cells_and_kzg_proofs(
+    Cell *recovered_cells,
+    KZGProof *recovered_proofs,
+    const uint64_t *cell_indices,
+    const Cell *cells,
+    size_t num_cells,
+    const KZGSettings *s
+);
+
+C_KZG_RET verify_cell_kzg_proof_batch(
+    bool *ok,
+    const Bytes48 *commitments_bytes,
+    const uint64_t *cell_indices,
+    const Cell *cells,
+    const Bytes48 *proofs_bytes,
+    size_t num_cells,
+    const KZGSettings *s
+);
+
 #ifdef __cplusplus
 }
 #endif
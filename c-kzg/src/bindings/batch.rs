@@ -0,0 +1,189 @@
+//! The thread pool behind the batch APIs.
+
+use super::Error;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of worker threads in the pool shared by the batch APIs.
+const BATCH_THREADS: usize = 8;
+
+lazy_static::lazy_static! {
+    /// The pool shared by the batch APIs, started on first use. `ThreadPool` is not `Sync`, so
+    /// each call clones a handle to it under the lock.
+    static ref BATCH_POOL: std::sync::Mutex<threadpool::ThreadPool> =
+        std::sync::Mutex::new(threadpool::ThreadPool::new(BATCH_THREADS));
+}
+
+/// The job of one `batch_map` call.
+type BatchFn<'a, T> = dyn Fn(usize) -> Result<T, Error> + Sync + 'a;
+
+// The state of one `batch_map` call, shared by the caller and the pool jobs. Items are claimed
+// through `next` by the jobs and by the caller alike, so the caller never waits for an item that
+// nobody has started, even when the pool is busy. `f` borrows from the caller's frame, but it is
+// only called for a claimed item and the caller runs `close` before that frame goes away, even
+// when it unwinds. Each slot is written by the single claimant of its item and only read once
+// `close` has returned.
+struct Batch<T> {
+    f: *const BatchFn<'static, T>,
+    slots: Vec<UnsafeCell<Option<Result<T, Error>>>>,
+    next: AtomicUsize,
+    finished: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for Batch<T> {}
+unsafe impl<T: Send> Sync for Batch<T> {}
+
+impl<T> Batch<T> {
+    /// Evaluates unclaimed items until there are none left.
+    fn run(&self) {
+        // Counts the claimed item as finished even if `f` unwinds; its slot then stays empty.
+        struct Finish<'a>(&'a AtomicUsize);
+        impl Drop for Finish<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::Release);
+            }
+        }
+
+        loop {
+            let i = self.next.fetch_add(1, Ordering::Relaxed);
+            if i >= self.slots.len() {
+                return;
+            }
+            let _finish = Finish(&self.finished);
+            let res = unsafe { (*self.f)(i) };
+            unsafe { *self.slots[i].get() = Some(res) };
+        }
+    }
+
+    /// Stops further claims and waits until every claimed item is finished. Calling it again
+    /// returns at once.
+    fn close(&self) {
+        let len = self.slots.len();
+        let claimed = self.next.swap(len, Ordering::Relaxed);
+        if claimed < len {
+            // Nobody will run the unclaimed items.
+            self.finished.fetch_add(len - claimed, Ordering::Relaxed);
+        }
+        while self.finished.load(Ordering::Acquire) < len {
+            std::thread::yield_now();
+        }
+    }
+}
+
+// Closes the batch when `batch_map` returns or unwinds.
+struct CloseOnDrop<T>(Arc<Batch<T>>);
+
+impl<T> Drop for CloseOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+/// Computes `f(0), ..., f(n - 1)` on the shared pool, returning the first error by index. The
+/// calling thread works on the items too, so this may also be called from a pool job.
+pub(super) fn batch_map<T, F>(n: usize, f: F) -> Result<Vec<T>, Error>
+where
+    T: Send + 'static,
+    F: Fn(usize) -> Result<T, Error> + Sync,
+{
+    if n <= 1 {
+        return (0..n).map(f).collect();
+    }
+
+    let f: *const BatchFn<'_, T> = &f as &BatchFn<'_, T>;
+    let batch = CloseOnDrop(Arc::new(Batch {
+        // SAFETY: only the lifetime is erased, and `CloseOnDrop` keeps `f` from being called
+        // once this frame is left.
+        f: unsafe { core::mem::transmute::<*const BatchFn<'_, T>, *const BatchFn<'static, T>>(f) },
+        slots: (0..n).map(|_| UnsafeCell::new(None)).collect(),
+        next: AtomicUsize::new(0),
+        finished: AtomicUsize::new(0),
+    }));
+
+    let pool = BATCH_POOL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    for _ in 1..core::cmp::min(n, pool.max_count() + 1) {
+        let batch = batch.0.clone();
+        pool.execute(move || batch.run());
+    }
+    batch.0.run();
+    batch.0.close();
+
+    batch
+        .0
+        .slots
+        .iter()
+        .map(|slot| unsafe { (*slot.get()).take() }.expect("kzg worker panicked"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn test_batch_map_order_and_errors() {
+        let squares = batch_map(100, |i| Ok(i * i)).unwrap();
+        assert_eq!(squares, (0..100).map(|i| i * i).collect::<Vec<_>>());
+        assert!(batch_map(0, Ok).unwrap().is_empty());
+
+        // The error of the lowest index wins, whichever thread finds it first.
+        let res = batch_map(50, |i| match i {
+            7 | 31 => Err(Error::MismatchLength(i.to_string())),
+            _ => Ok(i),
+        });
+        assert!(matches!(res, Err(Error::MismatchLength(s)) if s == "7"));
+    }
+
+    #[test]
+    fn test_batch_map_inside_pool() {
+        // Every pool thread blocks in a nested call; the callers do the work themselves.
+        let outer = batch_map(BATCH_THREADS * 2, |i| {
+            let inner = batch_map(4, |j| Ok(i * 4 + j))?;
+            Ok(inner.iter().sum::<usize>())
+        })
+        .unwrap();
+        for (i, sum) in outer.into_iter().enumerate() {
+            assert_eq!(sum, 16 * i + 6);
+        }
+    }
+
+    #[test]
+    fn test_batch_map_panics() {
+        // A panicking item fails the call, on whichever thread it ran.
+        let res = panic::catch_unwind(|| {
+            batch_map(16, |i| {
+                assert_ne!(i, 9);
+                Ok(i)
+            })
+        });
+        assert!(res.is_err());
+
+        // Once the call has unwound no further item is started.
+        let done = AtomicBool::new(false);
+        let late = AtomicUsize::new(0);
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            batch_map(64, |i| {
+                if done.load(Ordering::SeqCst) {
+                    late.fetch_add(1, Ordering::SeqCst);
+                }
+                assert_ne!(i, 0);
+                std::thread::sleep(std::time::Duration::from_millis(1));
+                Ok(i)
+            })
+        }));
+        done.store(true, Ordering::SeqCst);
+        assert!(res.is_err());
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(late.load(Ordering::SeqCst), 0);
+
+        // The pool is still usable.
+        assert_eq!(batch_map(3, Ok).unwrap(), [0, 1, 2]);
+    }
+}
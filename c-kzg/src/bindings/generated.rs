@@ -5,8 +5,30 @@ use libc::FILE;
 pub const BYTES_PER_COMMITMENT: usize = 48;
 pub const BYTES_PER_PROOF: usize = 48;
 pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+#[cfg(not(feature = "minimal-spec"))]
 pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+#[cfg(feature = "minimal-spec")]
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4;
+#[cfg(not(feature = "minimal-spec"))]
 pub const BYTES_PER_BLOB: usize = 131072;
+#[cfg(feature = "minimal-spec")]
+pub const BYTES_PER_BLOB: usize = 128;
+#[cfg(not(feature = "minimal-spec"))]
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = 8192;
+#[cfg(feature = "minimal-spec")]
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = 8;
+#[cfg(not(feature = "minimal-spec"))]
+pub const FIELD_ELEMENTS_PER_CELL: usize = 64;
+#[cfg(feature = "minimal-spec")]
+pub const FIELD_ELEMENTS_PER_CELL: usize = 2;
+#[cfg(not(feature = "minimal-spec"))]
+pub const CELLS_PER_EXT_BLOB: usize = 128;
+#[cfg(feature = "minimal-spec")]
+pub const CELLS_PER_EXT_BLOB: usize = 4;
+#[cfg(not(feature = "minimal-spec"))]
+pub const BYTES_PER_CELL: usize = 2048;
+#[cfg(feature = "minimal-spec")]
+pub const BYTES_PER_CELL: usize = 64;
 pub type limb_t = u64;
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -56,7 +78,13 @@ pub struct Bytes48 {
 #[repr(C)]
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub struct Blob {
-    bytes: [u8; 131072usize],
+    bytes: [u8; BYTES_PER_BLOB],
+}
+#[doc = " A single cell: `FIELD_ELEMENTS_PER_CELL` evaluations of the\n extended blob polynomial."]
+#[repr(C)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct Cell {
+    bytes: [u8; BYTES_PER_CELL],
 }
 #[repr(C)]
 #[doc = " The common return type for all routines in which something can go wrong."]
@@ -77,17 +105,26 @@ pub enum C_KZG_RET {
 pub struct KZGSettings {
     #[doc = " The length of `roots_of_unity`, a power of 2."]
     max_width: u64,
-    #[doc = " Powers of the primitive root of unity determined by\n `SCALE2_ROOT_OF_UNITY` in bit-reversal permutation order,\n length `max_width`."]
+    #[doc = " Powers of the primitive root of unity determined by\n `SCALE2_ROOT_OF_UNITY` in bit-reversal permutation order,\n length `max_width`. The first `FIELD_ELEMENTS_PER_BLOB` of them are\n the blob domain."]
     roots_of_unity: *mut fr_t,
+    #[doc = " The same powers in natural order, length `max_width + 1`."]
+    expanded_roots_of_unity: *mut fr_t,
+    #[doc = " The inverse powers in natural order, length `max_width + 1`."]
+    reverse_roots_of_unity: *mut fr_t,
     #[doc = " G1 group elements from the trusted setup,\n in Lagrange form bit-reversal permutation."]
     g1_values: *mut g1_t,
+    #[doc = " G1 group elements from the trusted setup, in monomial form, or NULL\n if the setup was loaded without them."]
+    g1_values_monomial: *mut g1_t,
     #[doc = " G2 group elements from the trusted setup."]
     g2_values: *mut g2_t,
+    #[doc = " FK20 precomputation for cell proofs: `CELLS_PER_EXT_BLOB` rows of\n `FIELD_ELEMENTS_PER_CELL` points, or NULL without monomial points."]
+    x_ext_fft_columns: *mut g1_t,
 }
 extern "C" {
     pub fn load_trusted_setup(
         out: *mut KZGSettings,
-        g1_bytes: *const u8,
+        g1_monomial_bytes: *const u8,
+        g1_lagrange_bytes: *const u8,
         n1: usize,
         g2_bytes: *const u8,
         n2: usize,
@@ -106,6 +143,12 @@ extern "C" {
         z_bytes: *const Bytes32,
         s: *const KZGSettings,
     ) -> C_KZG_RET;
+    pub fn evaluate_blob_at(
+        y_out: *mut Bytes32,
+        blob: *const Blob,
+        z_bytes: *const Bytes32,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
     pub fn compute_blob_kzg_proof(
         out: *mut KZGProof,
         blob: *const Blob,
@@ -134,5 +177,37 @@ extern "C" {
         proofs_bytes: *const Bytes48,
         n: usize,
         s: *const KZGSettings,
+    ) -> C_KZG_RET;    pub fn compute_cells_and_kzg_proofs(
+        cells: *mut Cell,
+        proofs: *mut KZGProof,
+        blob: *const Blob,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+    pub fn blob_to_coefficients(
+        out: *mut Blob,
+        blob: *const Blob,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+    pub fn coefficients_to_blob(
+        out: *mut Blob,
+        coeffs: *const Blob,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+    pub fn recover_cells_and_kzg_proofs(
+        recovered_cells: *mut Cell,
+        recovered_proofs: *mut KZGProof,
+        cell_indices: *const u64,
+        cells: *const Cell,
+        num_cells: usize,
+        s: *const KZGSettings,
+    ) -> C_KZG_RET;
+    pub fn verify_cell_kzg_proof_batch(
+        ok: *mut bool,
+        commitments_bytes: *const Bytes48,
+        cell_indices: *const u64,
+        cells: *const Cell,
+        proofs_bytes: *const Bytes48,
+        num_cells: usize,
+        s: *const KZGSettings,
     ) -> C_KZG_RET;
 }
\ No newline at end of file
@@ -0,0 +1,1453 @@
+//! A pure-Rust port of `c_kzg_4844.c`, built on the safe `blst` point types.
+//!
+//! It provides the same items as `generated.rs`, down to the raw-pointer function signatures, so
+//! the safe wrappers in the parent module work unchanged with either backend. The algorithms
+//! follow the C code step by step; see there for the details.
+
+use super::{KZGCommitment, KZGProof, NUM_G2_POINTS};
+use alloc::vec::Vec;
+use blst::msm::G1Affines;
+use blst::points::{G1Point, G2Point, Gt, Scalar};
+use blst::sha256::Sha256;
+use core::{ptr, slice};
+use libc::FILE;
+
+pub const BYTES_PER_COMMITMENT: usize = 48;
+pub const BYTES_PER_PROOF: usize = 48;
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+#[cfg(not(feature = "minimal-spec"))]
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+#[cfg(feature = "minimal-spec")]
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4;
+pub const BYTES_PER_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * BYTES_PER_FIELD_ELEMENT;
+pub const FIELD_ELEMENTS_PER_EXT_BLOB: usize = FIELD_ELEMENTS_PER_BLOB * 2;
+/// 64 as in the consensus specs, or half a blob when the blob is smaller than that. See the C
+/// header.
+pub const FIELD_ELEMENTS_PER_CELL: usize = if FIELD_ELEMENTS_PER_BLOB >= 64 {
+    64
+} else {
+    FIELD_ELEMENTS_PER_BLOB / 2
+};
+pub const CELLS_PER_EXT_BLOB: usize = FIELD_ELEMENTS_PER_EXT_BLOB / FIELD_ELEMENTS_PER_CELL;
+pub const BYTES_PER_CELL: usize = FIELD_ELEMENTS_PER_CELL * BYTES_PER_FIELD_ELEMENT;
+
+/// An array of 32 bytes. Represents an untrusted
+/// (potentially invalid) field element.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Bytes32 {
+    pub(super) bytes: [u8; 32],
+}
+
+/// An array of 48 bytes. Represents an untrusted
+/// (potentially invalid) commitment/proof.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Bytes48 {
+    pub(super) bytes: [u8; 48],
+}
+
+/// A basic blob data.
+#[repr(C)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct Blob {
+    pub(super) bytes: [u8; BYTES_PER_BLOB],
+}
+
+/// A single cell: `FIELD_ELEMENTS_PER_CELL` evaluations of the
+/// extended blob polynomial.
+#[repr(C)]
+#[derive(Debug, Hash, PartialEq, Eq)]
+pub struct Cell {
+    pub(super) bytes: [u8; BYTES_PER_CELL],
+}
+
+/// The common return type for all routines in which something can go wrong.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub enum C_KZG_RET {
+    /// Success!
+    C_KZG_OK = 0,
+    /// The supplied data is invalid in some way.
+    C_KZG_BADARGS = 1,
+    /// Internal error - this should never occur.
+    C_KZG_ERROR = 2,
+    /// Could not allocate memory.
+    C_KZG_MALLOC = 3,
+}
+
+/// Stores the setup and parameters needed for computing KZG proofs.
+#[derive(Debug)]
+pub struct KZGSettings {
+    /// The length of `roots_of_unity`, a power of 2.
+    max_width: usize,
+    /// Powers of the primitive root of unity in bit-reversal permutation order, length
+    /// `max_width`. The first `FIELD_ELEMENTS_PER_BLOB` of them are the blob domain.
+    roots_of_unity: Vec<Scalar>,
+    /// The same powers in natural order, length `max_width + 1`.
+    expanded_roots_of_unity: Vec<Scalar>,
+    /// The inverse powers in natural order, length `max_width + 1`.
+    reverse_roots_of_unity: Vec<Scalar>,
+    /// G1 group elements from the trusted setup, in Lagrange form bit-reversal permutation.
+    g1_values: Vec<G1Point>,
+    /// `g1_values` prepared for multi-scalar multiplication.
+    g1_values_affines: G1Affines,
+    /// G1 group elements from the trusted setup, in monomial form, or empty if the setup was
+    /// loaded without them.
+    g1_values_monomial: Vec<G1Point>,
+    /// G2 group elements from the trusted setup.
+    g2_values: Vec<G2Point>,
+    /// FK20 precomputation for cell proofs: `CELLS_PER_EXT_BLOB` rows of
+    /// `FIELD_ELEMENTS_PER_CELL` points, or empty without monomial points.
+    x_ext_fft_columns: Vec<G1Affines>,
+}
+
+type Result<T> = core::result::Result<T, C_KZG_RET>;
+
+macro_rules! check {
+    ($cond:expr) => {
+        if !$cond {
+            return Err(C_KZG_RET::C_KZG_BADARGS);
+        }
+    };
+}
+
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8] = b"RCKZGBATCH___V1_";
+const RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN: &[u8] = b"RCKZGCBATCH__V1_";
+
+/// The coset shift used when recovering cells.
+const RECOVERY_SHIFT_FACTOR: u64 = 7;
+
+/// `SCALE2_ROOT_OF_UNITY[i]` is a 2^i'th root of unity, as little-endian 64-bit limbs.
+#[rustfmt::skip]
+const SCALE2_ROOT_OF_UNITY: [[u64; 4]; 32] = [
+    [0x0000000000000001, 0x0000000000000000, 0x0000000000000000, 0x0000000000000000],
+    [0xffffffff00000000, 0x53bda402fffe5bfe, 0x3339d80809a1d805, 0x73eda753299d7d48],
+    [0x0001000000000000, 0xec03000276030000, 0x8d51ccce760304d0, 0x0000000000000000],
+    [0x7228fd3397743f7a, 0xb38b21c28713b700, 0x8c0625cd70d77ce2, 0x345766f603fa66e7],
+    [0x53ea61d87742bcce, 0x17beb312f20b6f76, 0xdd1c0af834cec32c, 0x20b1ce9140267af9],
+    [0x360c60997369df4e, 0xbf6e88fb4c38fb8a, 0xb4bcd40e22f55448, 0x50e0903a157988ba],
+    [0x8140d032f0a9ee53, 0x2d967f4be2f95155, 0x14a1e27164d8fdbd, 0x45af6345ec055e4d],
+    [0x5130c2c1660125be, 0x98d0caac87f5713c, 0xb7c68b4d7fdd60d0, 0x6898111413588742],
+    [0x4935bd2f817f694b, 0x0a0865a899e8deff, 0x6b368121ac0cf4ad, 0x4f9b4098e2e9f12e],
+    [0x4541b8ff2ee0434e, 0xd697168a3a6000fe, 0x39feec240d80689f, 0x095166525526a654],
+    [0x3c28d666a5c2d854, 0xea437f9626fc085e, 0x8f4de02c0f776af3, 0x325db5c3debf77a1],
+    [0x4a838b5d59cd79e5, 0x55ea6811be9c622d, 0x09f1ca610a08f166, 0x6d031f1b5c49c834],
+    [0xe206da11a5d36306, 0x0ad1347b378fbf96, 0xfc3e8acfe0f8245f, 0x564c0a11a0f704f4],
+    [0x6fdd00bfc78c8967, 0x146b58bc434906ac, 0x2ccddea2972e89ed, 0x485d512737b1da3d],
+    [0x034d2ff22a5ad9e1, 0xae4622f6a9152435, 0xdc86b01c0d477fa6, 0x56624634b500a166],
+    [0xfbd047e11279bb6e, 0xc8d5f51db3f32699, 0x483405417a0cbe39, 0x3291357ee558b50d],
+    [0xd7118f85cd96b8ad, 0x67a665ae1fcadc91, 0x88f39a78f1aeb578, 0x2155379d12180caa],
+    [0x08692405f3b70f10, 0xcd7f2bd6d0711b7d, 0x473a2eef772c33d6, 0x224262332d8acbf4],
+    [0x6f421a7d8ef674fb, 0xbb97a3bf30ce40fd, 0x652f717ae1c34bb0, 0x2d3056a530794f01],
+    [0x194e8c62ecb38d9d, 0xad8e16e84419c750, 0xdf625e80d0adef90, 0x520e587a724a6955],
+    [0xfece7e0e39898d4b, 0x2f69e02d265e09d9, 0xa57a6e07cb98de4a, 0x03e1c54bcb947035],
+    [0xcd3979122d3ea03a, 0x46b3105f04db5844, 0xc70d0874b0691d4e, 0x47c8b5817018af4f],
+    [0xc6e7a6ffb08e3363, 0xe08fec7c86389bee, 0xf2d38f10fbb8d1bb, 0x0abe6a5e5abcaa32],
+    [0x5616c57de0ec9eae, 0xc631ffb2585a72db, 0x5121af06a3b51e3c, 0x73560252aa0655b2],
+    [0x92cf4deb77bd779c, 0x72cf6a8029b7d7bc, 0x6e0bcd91ee762730, 0x291cf6d68823e687],
+    [0xce32ef844e11a51e, 0xc0ba12bb3da64ca5, 0x0454dc1edc61a1a3, 0x019fe632fd328739],
+    [0x531a11a0d2d75182, 0x02c8118402867ddc, 0x116168bffbedc11d, 0x0a0a77a3b1980c0d],
+    [0xe2d0a7869f0319ed, 0xb94f1101b1d7a628, 0xece8ea224f31d25d, 0x23397a9300f8f98b],
+    [0xd7b688830a4f2089, 0x6558e9e3f6ac7b41, 0x99e276b571905a7d, 0x52dd465e2f094256],
+    [0x474650359d8e211b, 0x84d37b826214abc6, 0x8da40c1ef2bb4598, 0x0c83ea7744bf1bee],
+    [0x694341f608c9dd56, 0xed3a181fabb30adc, 0x1339a815da8b398f, 0x2c6d4e4511657e1e],
+    [0x63e7cb4906ffc93f, 0xf070bb00e28a193d, 0xad1715b02e5713b5, 0x4b5371495990693f],
+];
+
+///////////////////////////////////////////////////////////////////////////////
+// Field and group helpers
+///////////////////////////////////////////////////////////////////////////////
+
+fn fr_pow(a: &Scalar, mut n: u64) -> Scalar {
+    let mut tmp = *a;
+    let mut out = Scalar::one();
+    loop {
+        if n & 1 == 1 {
+            out *= tmp;
+        }
+        n >>= 1;
+        if n == 0 {
+            return out;
+        }
+        tmp = tmp.square();
+    }
+}
+
+fn fr_inverse(a: &Scalar) -> Result<Scalar> {
+    a.inverse().ok_or(C_KZG_RET::C_KZG_BADARGS)
+}
+
+/// Inverts every element of `a` with a single field inversion. Fails on any zero input.
+fn fr_batch_inv(a: &[Scalar]) -> Result<Vec<Scalar>> {
+    let mut out = Vec::with_capacity(a.len());
+    let mut accumulator = Scalar::one();
+    for x in a {
+        out.push(accumulator);
+        accumulator *= *x;
+    }
+    accumulator = fr_inverse(&accumulator)?;
+    for i in (0..a.len()).rev() {
+        out[i] *= accumulator;
+        accumulator *= a[i];
+    }
+    Ok(out)
+}
+
+/// Returns `[x^0, x^1, ..., x^{n-1}]`.
+fn compute_powers(x: &Scalar, n: usize) -> Vec<Scalar> {
+    let mut current_power = Scalar::one();
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        out.push(current_power);
+        current_power *= *x;
+    }
+    out
+}
+
+/// Checks `e(a1, a2) == e(b1, b2)`.
+fn pairings_verify(a1: &G1Point, a2: &G2Point, b1: &G1Point, b2: &G2Point) -> bool {
+    Gt::multi_pairing(&[(-*a1, *a2), (*b1, *b2)]).is_one()
+}
+
+fn g1_lincomb_naive(points: &[G1Point], coeffs: &[Scalar]) -> G1Point {
+    points.iter().zip(coeffs).map(|(p, c)| *p * *c).sum()
+}
+
+fn g1_lincomb_fast(points: &G1Affines, coeffs: &[Scalar]) -> Result<G1Point> {
+    points.mult(coeffs).map_err(|_| C_KZG_RET::C_KZG_ERROR)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Bytes conversions
+///////////////////////////////////////////////////////////////////////////////
+
+fn hash_to_bls_field(bytes: &[u8; 32]) -> Scalar {
+    Scalar::from_bytes_be_reduced(bytes)
+}
+
+fn bytes_to_bls_field(bytes: &[u8]) -> Result<Scalar> {
+    Scalar::from_bytes_be(bytes).map_err(|_| C_KZG_RET::C_KZG_BADARGS)
+}
+
+/// Decompresses a commitment or proof. The point at infinity is accepted.
+fn validate_kzg_g1(bytes: &Bytes48) -> Result<G1Point> {
+    G1Point::from_compressed(&bytes.bytes).map_err(|_| C_KZG_RET::C_KZG_BADARGS)
+}
+
+fn blob_to_polynomial(blob: &Blob) -> Result<Vec<Scalar>> {
+    blob.bytes
+        .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .map(bytes_to_bls_field)
+        .collect()
+}
+
+fn cell_to_fr_array(cell: &Cell) -> Result<Vec<Scalar>> {
+    cell.bytes
+        .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .map(bytes_to_bls_field)
+        .collect()
+}
+
+/// Returns the Fiat-Shamir challenge required to verify `blob` and `commitment`.
+fn compute_challenge(blob: &Blob, commitment: &G1Point) -> Scalar {
+    let mut hasher = Sha256::new();
+    hasher.update(FIAT_SHAMIR_PROTOCOL_DOMAIN);
+    hasher.update(&0u64.to_be_bytes());
+    hasher.update(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    hasher.update(&blob.bytes);
+    hasher.update(&commitment.compress());
+    hash_to_bls_field(&hasher.finalize())
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// KZG functions
+///////////////////////////////////////////////////////////////////////////////
+
+fn evaluate_polynomial_in_evaluation_form(
+    p: &[Scalar],
+    x: &Scalar,
+    s: &KZGSettings,
+) -> Result<Scalar> {
+    let roots_of_unity = &s.roots_of_unity[..FIELD_ELEMENTS_PER_BLOB];
+    let mut inverses_in = Vec::with_capacity(FIELD_ELEMENTS_PER_BLOB);
+    for (i, root) in roots_of_unity.iter().enumerate() {
+        // The barycentric formula divides by zero on the domain itself.
+        if x == root {
+            return Ok(p[i]);
+        }
+        inverses_in.push(*x - *root);
+    }
+    let inverses = fr_batch_inv(&inverses_in)?;
+
+    let mut out = Scalar::zero();
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        out += inverses[i] * roots_of_unity[i] * p[i];
+    }
+    out *= fr_inverse(&Scalar::from_u64(FIELD_ELEMENTS_PER_BLOB as u64))?;
+    out *= fr_pow(x, FIELD_ELEMENTS_PER_BLOB as u64) - Scalar::one();
+    Ok(out)
+}
+
+fn verify_kzg_proof_impl(
+    commitment: &G1Point,
+    z: &Scalar,
+    y: &Scalar,
+    proof: &G1Point,
+    s: &KZGSettings,
+) -> bool {
+    let x_minus_z = s.g2_values[1] - G2Point::generator() * *z;
+    let p_minus_y = *commitment - G1Point::generator() * *y;
+    // P - y = Q * (X - z)
+    pairings_verify(&p_minus_y, &G2Point::generator(), proof, &x_minus_z)
+}
+
+fn compute_kzg_proof_impl(
+    polynomial: &[Scalar],
+    z: &Scalar,
+    s: &KZGSettings,
+) -> Result<(G1Point, Scalar)> {
+    let y = evaluate_polynomial_in_evaluation_form(polynomial, z, s)?;
+    let roots_of_unity = &s.roots_of_unity[..FIELD_ELEMENTS_PER_BLOB];
+
+    // `m` is the index of `z` in the domain, if it is there.
+    let mut m = None;
+    let mut q = vec![Scalar::zero(); FIELD_ELEMENTS_PER_BLOB];
+    let mut inverses_in = vec![Scalar::one(); FIELD_ELEMENTS_PER_BLOB];
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        if *z == roots_of_unity[i] {
+            m = Some(i);
+            continue;
+        }
+        // (p_i - y) / (ω_i - z)
+        q[i] = polynomial[i] - y;
+        inverses_in[i] = roots_of_unity[i] - *z;
+    }
+    let inverses = fr_batch_inv(&inverses_in)?;
+    for i in 0..FIELD_ELEMENTS_PER_BLOB {
+        q[i] *= inverses[i];
+    }
+
+    if let Some(m) = m {
+        q[m] = Scalar::zero();
+        for i in 0..FIELD_ELEMENTS_PER_BLOB {
+            if i != m {
+                // Denominator: z * (z - ω_i)
+                inverses_in[i] = (*z - roots_of_unity[i]) * *z;
+            }
+        }
+        let inverses = fr_batch_inv(&inverses_in)?;
+        for i in 0..FIELD_ELEMENTS_PER_BLOB {
+            if i != m {
+                // (p_i - y) * ω_i / (z * (z - ω_i))
+                let tmp = (polynomial[i] - y) * roots_of_unity[i] * inverses[i];
+                q[m] += tmp;
+            }
+        }
+    }
+
+    let proof = g1_lincomb_fast(&s.g1_values_affines, &q)?;
+    Ok((proof, y))
+}
+
+/// Computes the powers of the random challenge used to batch KZG proofs.
+fn compute_r_powers(
+    commitments_g1: &[G1Point],
+    zs_fr: &[Scalar],
+    ys_fr: &[Scalar],
+    proofs_g1: &[G1Point],
+) -> Vec<Scalar> {
+    let n = commitments_g1.len();
+    let mut hasher = Sha256::new();
+    hasher.update(RANDOM_CHALLENGE_KZG_BATCH_DOMAIN);
+    hasher.update(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    hasher.update(&(n as u64).to_be_bytes());
+    for i in 0..n {
+        hasher.update(&commitments_g1[i].compress());
+        hasher.update(&zs_fr[i].to_bytes_be());
+        hasher.update(&ys_fr[i].to_bytes_be());
+        hasher.update(&proofs_g1[i].compress());
+    }
+    let r = hash_to_bls_field(&hasher.finalize());
+    compute_powers(&r, n)
+}
+
+fn verify_kzg_proof_batch(
+    commitments_g1: &[G1Point],
+    zs_fr: &[Scalar],
+    ys_fr: &[Scalar],
+    proofs_g1: &[G1Point],
+    s: &KZGSettings,
+) -> bool {
+    let r_powers = compute_r_powers(commitments_g1, zs_fr, ys_fr, proofs_g1);
+
+    // \sum r^i * Proof_i
+    let proof_lincomb = g1_lincomb_naive(proofs_g1, &r_powers);
+
+    let mut c_minus_y = Vec::with_capacity(commitments_g1.len());
+    let mut r_times_z = Vec::with_capacity(commitments_g1.len());
+    for i in 0..commitments_g1.len() {
+        c_minus_y.push(commitments_g1[i] - G1Point::generator() * ys_fr[i]);
+        r_times_z.push(r_powers[i] * zs_fr[i]);
+    }
+
+    // \sum r^i z_i Proof_i + \sum r^i (C_i - [y_i])
+    let rhs_g1 = g1_lincomb_naive(&c_minus_y, &r_powers) + g1_lincomb_naive(proofs_g1, &r_times_z);
+
+    pairings_verify(
+        &proof_lincomb,
+        &s.g2_values[1],
+        &rhs_g1,
+        &G2Point::generator(),
+    )
+}
+
+fn verify_blob_kzg_proof_impl(
+    blob: &Blob,
+    commitment_bytes: &Bytes48,
+    proof_bytes: &Bytes48,
+    s: &KZGSettings,
+) -> Result<bool> {
+    let commitment = validate_kzg_g1(commitment_bytes)?;
+    let polynomial = blob_to_polynomial(blob)?;
+    let proof = validate_kzg_g1(proof_bytes)?;
+    let z = compute_challenge(blob, &commitment);
+    let y = evaluate_polynomial_in_evaluation_form(&polynomial, &z, s)?;
+    Ok(verify_kzg_proof_impl(&commitment, &z, &y, &proof, s))
+}
+
+fn verify_blob_kzg_proof_batch_impl(
+    blobs: &[Blob],
+    commitments_bytes: &[Bytes48],
+    proofs_bytes: &[Bytes48],
+    s: &KZGSettings,
+) -> Result<bool> {
+    match blobs.len() {
+        0 => return Ok(true),
+        1 => {
+            return verify_blob_kzg_proof_impl(
+                &blobs[0],
+                &commitments_bytes[0],
+                &proofs_bytes[0],
+                s,
+            )
+        }
+        _ => {}
+    }
+
+    let n = blobs.len();
+    let mut commitments_g1 = Vec::with_capacity(n);
+    let mut proofs_g1 = Vec::with_capacity(n);
+    let mut zs_fr = Vec::with_capacity(n);
+    let mut ys_fr = Vec::with_capacity(n);
+    for i in 0..n {
+        let commitment = validate_kzg_g1(&commitments_bytes[i])?;
+        let polynomial = blob_to_polynomial(&blobs[i])?;
+        let z = compute_challenge(&blobs[i], &commitment);
+        ys_fr.push(evaluate_polynomial_in_evaluation_form(&polynomial, &z, s)?);
+        zs_fr.push(z);
+        commitments_g1.push(commitment);
+        proofs_g1.push(validate_kzg_g1(&proofs_bytes[i])?);
+    }
+
+    Ok(verify_kzg_proof_batch(
+        &commitments_g1,
+        &zs_fr,
+        &ys_fr,
+        &proofs_g1,
+        s,
+    ))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Trusted setup
+///////////////////////////////////////////////////////////////////////////////
+
+fn log2_pow2(n: usize) -> u32 {
+    n.trailing_zeros()
+}
+
+fn reverse_bits_limited(i: usize, n: usize) -> usize {
+    ((i as u32).reverse_bits() >> (32 - log2_pow2(n))) as usize
+}
+
+/// Reorders `values` in place so that `values[i]` moves to the bit-reversal of `i`.
+fn bit_reversal_permutation<T>(values: &mut [T]) -> Result<()> {
+    let n = values.len();
+    check!(n.is_power_of_two() && n > 1 && n >> 32 == 0);
+    for i in 0..n {
+        let r = reverse_bits_limited(i, n);
+        if r > i {
+            values.swap(i, r);
+        }
+    }
+    Ok(())
+}
+
+/// Returns `[1, root, root^2, ..., root^width]`, checking that `root` has order `width`.
+fn expand_root_of_unity(root: &Scalar, width: usize) -> Result<Vec<Scalar>> {
+    check!(width >= 2);
+    let mut out = Vec::with_capacity(width + 1);
+    out.push(Scalar::one());
+    out.push(*root);
+    while out.len() <= width {
+        let next = out[out.len() - 1] * *root;
+        if next == Scalar::one() && out.len() < width {
+            return Err(C_KZG_RET::C_KZG_BADARGS);
+        }
+        out.push(next);
+    }
+    check!(out[width] == Scalar::one());
+    Ok(out)
+}
+
+/// Returns the roots of unity in bit-reversed, natural and inverse natural order.
+fn compute_roots_of_unity(max_width: usize) -> Result<(Vec<Scalar>, Vec<Scalar>, Vec<Scalar>)> {
+    let max_scale = log2_pow2(max_width) as usize;
+    check!(max_scale < SCALE2_ROOT_OF_UNITY.len());
+    let mut root_bytes = [0u8; 32];
+    for (chunk, limb) in root_bytes
+        .chunks_exact_mut(8)
+        .zip(&SCALE2_ROOT_OF_UNITY[max_scale])
+    {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    let root_of_unity = Scalar::from_bytes_le(&root_bytes).map_err(|_| C_KZG_RET::C_KZG_ERROR)?;
+
+    let expanded = expand_root_of_unity(&root_of_unity, max_width)?;
+    // The inverse of w^i is w^(max_width - i)
+    let reverse = expanded.iter().rev().copied().collect();
+    let mut roots = expanded[..max_width].to_vec();
+    bit_reversal_permutation(&mut roots)?;
+    Ok((roots, expanded, reverse))
+}
+
+fn g1_values_from_bytes(bytes: &[u8], n: usize) -> Result<Vec<G1Point>> {
+    check!(bytes.len() == n * BYTES_PER_COMMITMENT);
+    bytes
+        .chunks_exact(BYTES_PER_COMMITMENT)
+        .map(|b| G1Point::from_compressed(b).map_err(|_| C_KZG_RET::C_KZG_BADARGS))
+        .collect()
+}
+
+fn load_trusted_setup_impl(
+    g1_monomial_bytes: Option<&[u8]>,
+    g1_lagrange_bytes: &[u8],
+    n1: usize,
+    g2_bytes: &[u8],
+) -> Result<KZGSettings> {
+    let g1_lagrange = g1_values_from_bytes(g1_lagrange_bytes, n1)?;
+    let g1_monomial = match g1_monomial_bytes {
+        Some(bytes) => Some(g1_values_from_bytes(bytes, n1)?),
+        None => None,
+    };
+    let g2_values = g2_bytes
+        .chunks_exact(96)
+        .map(|b| G2Point::from_compressed(b).map_err(|_| C_KZG_RET::C_KZG_BADARGS))
+        .collect::<Result<Vec<_>>>()?;
+
+    // If e(G1_SETUP[1], G2_SETUP[0]) == e(G1_SETUP[0], G2_SETUP[1]), the G1 points were given
+    // in monomial form rather than Lagrange form.
+    check!(!pairings_verify(
+        &g1_lagrange[1],
+        &g2_values[0],
+        &g1_lagrange[0],
+        &g2_values[1]
+    ));
+
+    // The roots cover the extended domain used by cells; the first half of the bit-reversed
+    // roots is exactly the blob domain.
+    let max_width = FIELD_ELEMENTS_PER_EXT_BLOB;
+    let (roots_of_unity, expanded_roots_of_unity, reverse_roots_of_unity) =
+        compute_roots_of_unity(max_width)?;
+    let mut s = KZGSettings {
+        max_width,
+        roots_of_unity,
+        expanded_roots_of_unity,
+        reverse_roots_of_unity,
+        g1_values: Vec::new(),
+        g1_values_affines: G1Affines::default(),
+        g1_values_monomial: Vec::new(),
+        g2_values,
+        x_ext_fft_columns: Vec::new(),
+    };
+
+    s.g1_values = g1_lagrange;
+    bit_reversal_permutation(&mut s.g1_values)?;
+    s.g1_values_affines = G1Affines::new(&s.g1_values);
+    // Without the monomial points only the EIP-4844 functions are available
+    if let Some(points) = g1_monomial {
+        s.g1_values_monomial = points;
+        s.x_ext_fft_columns = init_fk20_columns(&s)?;
+    }
+    Ok(s)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Fast Fourier transforms
+///////////////////////////////////////////////////////////////////////////////
+
+fn fr_fft_fast(
+    out: &mut [Scalar],
+    input: &[Scalar],
+    stride: usize,
+    roots: &[Scalar],
+    roots_stride: usize,
+    n: usize,
+) {
+    let half = n / 2;
+    if half > 0 {
+        let (lo, hi) = out.split_at_mut(half);
+        fr_fft_fast(lo, input, stride * 2, roots, roots_stride * 2, half);
+        fr_fft_fast(
+            hi,
+            &input[stride..],
+            stride * 2,
+            roots,
+            roots_stride * 2,
+            half,
+        );
+        for i in 0..half {
+            let y_times_root = hi[i] * roots[i * roots_stride];
+            hi[i] = lo[i] - y_times_root;
+            lo[i] += y_times_root;
+        }
+    } else {
+        out[0] = input[0];
+    }
+}
+
+fn fr_fft(input: &[Scalar], n: usize, s: &KZGSettings) -> Result<Vec<Scalar>> {
+    check!(n != 0 && n <= s.max_width && n.is_power_of_two() && input.len() >= n);
+    let mut out = vec![Scalar::zero(); n];
+    fr_fft_fast(
+        &mut out,
+        input,
+        1,
+        &s.expanded_roots_of_unity,
+        s.max_width / n,
+        n,
+    );
+    Ok(out)
+}
+
+fn fr_ifft(input: &[Scalar], n: usize, s: &KZGSettings) -> Result<Vec<Scalar>> {
+    check!(n != 0 && n <= s.max_width && n.is_power_of_two() && input.len() >= n);
+    let mut out = vec![Scalar::zero(); n];
+    fr_fft_fast(
+        &mut out,
+        input,
+        1,
+        &s.reverse_roots_of_unity,
+        s.max_width / n,
+        n,
+    );
+    let inv_len = fr_inverse(&Scalar::from_u64(n as u64))?;
+    for x in out.iter_mut() {
+        *x *= inv_len;
+    }
+    Ok(out)
+}
+
+/// Multiplies coefficient `i` of `p` by `shift^i`.
+fn shift_poly(p: &mut [Scalar], shift: &Scalar) {
+    let mut factor_power = Scalar::one();
+    for x in p.iter_mut().skip(1) {
+        factor_power *= *shift;
+        *x *= factor_power;
+    }
+}
+
+fn coset_fft(input: &[Scalar], n: usize, s: &KZGSettings) -> Result<Vec<Scalar>> {
+    let mut in_shifted = input[..n].to_vec();
+    shift_poly(&mut in_shifted, &Scalar::from_u64(RECOVERY_SHIFT_FACTOR));
+    fr_fft(&in_shifted, n, s)
+}
+
+fn coset_ifft(input: &[Scalar], n: usize, s: &KZGSettings) -> Result<Vec<Scalar>> {
+    let mut out = fr_ifft(input, n, s)?;
+    let inv_shift = fr_inverse(&Scalar::from_u64(RECOVERY_SHIFT_FACTOR))?;
+    shift_poly(&mut out, &inv_shift);
+    Ok(out)
+}
+
+fn g1_fft_fast(
+    out: &mut [G1Point],
+    input: &[G1Point],
+    stride: usize,
+    roots: &[Scalar],
+    roots_stride: usize,
+    n: usize,
+) {
+    let half = n / 2;
+    if half > 0 {
+        let (lo, hi) = out.split_at_mut(half);
+        g1_fft_fast(lo, input, stride * 2, roots, roots_stride * 2, half);
+        g1_fft_fast(
+            hi,
+            &input[stride..],
+            stride * 2,
+            roots,
+            roots_stride * 2,
+            half,
+        );
+        for i in 0..half {
+            // Skip the scalar multiplication where it is trivial
+            let y_times_root = if i == 0 || hi[i].is_identity() {
+                hi[i]
+            } else {
+                hi[i] * roots[i * roots_stride]
+            };
+            hi[i] = lo[i] - y_times_root;
+            lo[i] += y_times_root;
+        }
+    } else {
+        out[0] = input[0];
+    }
+}
+
+fn g1_fft(input: &[G1Point], n: usize, s: &KZGSettings) -> Result<Vec<G1Point>> {
+    check!(n != 0 && n <= s.max_width && n.is_power_of_two() && input.len() >= n);
+    let mut out = vec![G1Point::identity(); n];
+    g1_fft_fast(
+        &mut out,
+        input,
+        1,
+        &s.expanded_roots_of_unity,
+        s.max_width / n,
+        n,
+    );
+    Ok(out)
+}
+
+fn g1_ifft(input: &[G1Point], n: usize, s: &KZGSettings) -> Result<Vec<G1Point>> {
+    check!(n != 0 && n <= s.max_width && n.is_power_of_two() && input.len() >= n);
+    let mut out = vec![G1Point::identity(); n];
+    g1_fft_fast(
+        &mut out,
+        input,
+        1,
+        &s.reverse_roots_of_unity,
+        s.max_width / n,
+        n,
+    );
+    let inv_len = fr_inverse(&Scalar::from_u64(n as u64))?;
+    for p in out.iter_mut() {
+        *p *= inv_len;
+    }
+    Ok(out)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Cells (EIP-7594)
+///////////////////////////////////////////////////////////////////////////////
+
+/// Precomputes the FFTs of the setup columns used by FK20, one row of
+/// `FIELD_ELEMENTS_PER_CELL` points per position of the extended domain.
+fn init_fk20_columns(s: &KZGSettings) -> Result<Vec<G1Affines>> {
+    let k = FIELD_ELEMENTS_PER_BLOB / FIELD_ELEMENTS_PER_CELL;
+    let k2 = 2 * k;
+    let mut rows = vec![Vec::with_capacity(FIELD_ELEMENTS_PER_CELL); k2];
+    for b in 0..FIELD_ELEMENTS_PER_CELL {
+        let mut x = vec![G1Point::identity(); k2];
+        x[0] = s.g1_values_monomial[b];
+        for u in 1..k - 1 {
+            x[k2 - u] = s.g1_values_monomial[u * FIELD_ELEMENTS_PER_CELL + b];
+        }
+        let x_ext_fft = g1_fft(&x, k2, s)?;
+        for (row, point) in rows.iter_mut().zip(x_ext_fft) {
+            row.push(point);
+        }
+    }
+    Ok(rows.iter().map(|row| G1Affines::new(row)).collect())
+}
+
+/// Computes the proofs of all cells with FK20, in cell order.
+fn compute_fk20_proofs(poly: &[Scalar], s: &KZGSettings) -> Result<Vec<G1Point>> {
+    let k = FIELD_ELEMENTS_PER_BLOB / FIELD_ELEMENTS_PER_CELL;
+    let k2 = 2 * k;
+
+    // Transform the Toeplitz coefficients for each offset in a cell
+    let mut toeplitz_coeffs_fft = vec![Vec::with_capacity(FIELD_ELEMENTS_PER_CELL); k2];
+    for b in 0..FIELD_ELEMENTS_PER_CELL {
+        let mut circulant = vec![Scalar::zero(); k2];
+        for m in 0..k - 1 {
+            circulant[m] = poly[(m + 1) * FIELD_ELEMENTS_PER_CELL + b];
+        }
+        let circulant_fft = fr_fft(&circulant, k2, s)?;
+        for (row, x) in toeplitz_coeffs_fft.iter_mut().zip(circulant_fft) {
+            row.push(x);
+        }
+    }
+
+    // Pointwise products in the transformed domain, summed over offsets
+    let h_ext_fft = s
+        .x_ext_fft_columns
+        .iter()
+        .zip(&toeplitz_coeffs_fft)
+        .map(|(points, coeffs)| g1_lincomb_fast(points, coeffs))
+        .collect::<Result<Vec<_>>>()?;
+
+    // Only the first k - 1 entries of the circulant product are wanted
+    let mut h = g1_ifft(&h_ext_fft, k2, s)?;
+    for p in h.iter_mut().skip(k - 1) {
+        *p = G1Point::identity();
+    }
+
+    // Evaluate over the cosets, then put them in cell order
+    let mut out = g1_fft(&h, k2, s)?;
+    bit_reversal_permutation(&mut out)?;
+    Ok(out)
+}
+
+/// Extends the polynomial with coefficients `poly` to all cells and computes their proofs.
+fn compute_cells_and_kzg_proofs_impl(
+    poly: &[Scalar],
+    s: &KZGSettings,
+) -> Result<(Vec<Cell>, Vec<G1Point>)> {
+    // Evaluate over the extended domain, in bit-reversed order
+    let mut poly_ext = vec![Scalar::zero(); FIELD_ELEMENTS_PER_EXT_BLOB];
+    poly_ext[..FIELD_ELEMENTS_PER_BLOB].copy_from_slice(&poly[..FIELD_ELEMENTS_PER_BLOB]);
+    let mut data = fr_fft(&poly_ext, FIELD_ELEMENTS_PER_EXT_BLOB, s)?;
+    bit_reversal_permutation(&mut data)?;
+
+    let cells = data
+        .chunks_exact(FIELD_ELEMENTS_PER_CELL)
+        .map(|evals| {
+            let mut cell = Cell {
+                bytes: [0; BYTES_PER_CELL],
+            };
+            for (out, x) in cell
+                .bytes
+                .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+                .zip(evals)
+            {
+                out.copy_from_slice(&x.to_bytes_be());
+            }
+            cell
+        })
+        .collect();
+
+    Ok((cells, compute_fk20_proofs(poly, s)?))
+}
+
+fn compute_cells_and_kzg_proofs_from_blob(
+    blob: &Blob,
+    s: &KZGSettings,
+) -> Result<(Vec<Cell>, Vec<G1Point>)> {
+    // Cells need the monomial form points
+    check!(!s.g1_values_monomial.is_empty());
+
+    // The blob holds evaluations in bit-reversed order; get coefficients
+    let mut evals = blob_to_polynomial(blob)?;
+    bit_reversal_permutation(&mut evals)?;
+    let poly = fr_ifft(&evals, FIELD_ELEMENTS_PER_BLOB, s)?;
+    compute_cells_and_kzg_proofs_impl(&poly, s)
+}
+
+fn polynomial_to_blob(p: &[Scalar]) -> Blob {
+    let mut blob = Blob {
+        bytes: [0; BYTES_PER_BLOB],
+    };
+    for (out, x) in blob.bytes.chunks_exact_mut(BYTES_PER_FIELD_ELEMENT).zip(p) {
+        out.copy_from_slice(&x.to_bytes_be());
+    }
+    blob
+}
+
+fn blob_to_coefficients_impl(blob: &Blob, s: &KZGSettings) -> Result<Blob> {
+    let mut evals = blob_to_polynomial(blob)?;
+    bit_reversal_permutation(&mut evals)?;
+    let poly = fr_ifft(&evals, FIELD_ELEMENTS_PER_BLOB, s)?;
+    Ok(polynomial_to_blob(&poly))
+}
+
+fn coefficients_to_blob_impl(coeffs: &Blob, s: &KZGSettings) -> Result<Blob> {
+    let poly = blob_to_polynomial(coeffs)?;
+    let mut evals = fr_fft(&poly, FIELD_ELEMENTS_PER_BLOB, s)?;
+    bit_reversal_permutation(&mut evals)?;
+    Ok(polynomial_to_blob(&evals))
+}
+
+/// Returns the coefficients of the polynomial vanishing on every point of the missing cells.
+fn vanishing_polynomial_for_missing_cells(cell_indices: &[u64], s: &KZGSettings) -> Vec<Scalar> {
+    let mut present = [false; CELLS_PER_EXT_BLOB];
+    for &i in cell_indices {
+        present[i as usize] = true;
+    }
+
+    // Multiply out prod (X - h_i^l) one missing cell at a time
+    let mut short_poly = vec![Scalar::one()];
+    for (i, _) in present.iter().enumerate().filter(|(_, &p)| !p) {
+        let rev = reverse_bits_limited(i, CELLS_PER_EXT_BLOB);
+        let root = s.expanded_roots_of_unity[rev * FIELD_ELEMENTS_PER_CELL];
+        let len = short_poly.len();
+        short_poly.push(short_poly[len - 1]);
+        for j in (1..len).rev() {
+            short_poly[j] = short_poly[j - 1] - short_poly[j] * root;
+        }
+        short_poly[0] = -(short_poly[0] * root);
+    }
+
+    // Substitute X^l for X
+    let mut out = vec![Scalar::zero(); FIELD_ELEMENTS_PER_EXT_BLOB];
+    for (i, x) in short_poly.into_iter().enumerate() {
+        out[i * FIELD_ELEMENTS_PER_CELL] = x;
+    }
+    out
+}
+
+/// Recovers the coefficients of a blob polynomial from at least half of its cells.
+fn recover_polynomial_coeff(
+    cell_indices: &[u64],
+    cells: &[Cell],
+    s: &KZGSettings,
+) -> Result<Vec<Scalar>> {
+    let n = FIELD_ELEMENTS_PER_EXT_BLOB;
+
+    // Gather the known evaluations, with zeros for missing cells
+    let mut ext_evals = vec![Scalar::zero(); n];
+    for (&index, cell) in cell_indices.iter().zip(cells) {
+        let start = index as usize * FIELD_ELEMENTS_PER_CELL;
+        ext_evals[start..start + FIELD_ELEMENTS_PER_CELL].copy_from_slice(&cell_to_fr_array(cell)?);
+    }
+    bit_reversal_permutation(&mut ext_evals)?;
+
+    // With Z vanishing on the missing points, E * Z agrees with P * Z on the whole domain, and
+    // P * Z has degree below the domain size.
+    let zero_poly = vanishing_polynomial_for_missing_cells(cell_indices, s);
+    let zero_poly_eval = fr_fft(&zero_poly, n, s)?;
+    let product: Vec<Scalar> = ext_evals
+        .iter()
+        .zip(&zero_poly_eval)
+        .map(|(e, z)| *e * *z)
+        .collect();
+    let product_coeff = fr_ifft(&product, n, s)?;
+
+    // Divide by Z over a coset, where Z has no roots
+    let mut coset_product = coset_fft(&product_coeff, n, s)?;
+    let coset_zero_poly_inv = fr_batch_inv(&coset_fft(&zero_poly, n, s)?)?;
+    for (p, z) in coset_product.iter_mut().zip(&coset_zero_poly_inv) {
+        *p *= *z;
+    }
+    let mut out = coset_ifft(&coset_product, n, s)?;
+    out.truncate(FIELD_ELEMENTS_PER_BLOB);
+    Ok(out)
+}
+
+fn recover_cells_and_kzg_proofs_impl(
+    cell_indices: &[u64],
+    cells: &[Cell],
+    s: &KZGSettings,
+) -> Result<(Vec<Cell>, Vec<G1Point>)> {
+    // Cells need the monomial form points
+    check!(!s.g1_values_monomial.is_empty());
+
+    // We need at least half of the cells, and no more than all of them
+    check!(cells.len() >= CELLS_PER_EXT_BLOB / 2);
+    check!(cells.len() <= CELLS_PER_EXT_BLOB);
+
+    let mut seen = [false; CELLS_PER_EXT_BLOB];
+    for &i in cell_indices {
+        check!(i < CELLS_PER_EXT_BLOB as u64);
+        check!(!seen[i as usize]);
+        seen[i as usize] = true;
+    }
+
+    let poly = recover_polynomial_coeff(cell_indices, cells, s)?;
+    compute_cells_and_kzg_proofs_impl(&poly, s)
+}
+
+fn compute_cell_batch_challenge(
+    commitments: &[Bytes48],
+    commitment_indices: &[u64],
+    cell_indices: &[u64],
+    cells: &[Cell],
+    proofs_bytes: &[Bytes48],
+) -> Scalar {
+    let mut hasher = Sha256::new();
+    hasher.update(RANDOM_CHALLENGE_KZG_CELL_BATCH_DOMAIN);
+    hasher.update(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+    hasher.update(&(FIELD_ELEMENTS_PER_CELL as u64).to_be_bytes());
+    hasher.update(&(commitments.len() as u64).to_be_bytes());
+    hasher.update(&(cells.len() as u64).to_be_bytes());
+    for commitment in commitments {
+        hasher.update(&commitment.bytes);
+    }
+    for i in 0..cells.len() {
+        hasher.update(&commitment_indices[i].to_be_bytes());
+        hasher.update(&cell_indices[i].to_be_bytes());
+        hasher.update(&cells[i].bytes);
+        hasher.update(&proofs_bytes[i].bytes);
+    }
+    hash_to_bls_field(&hasher.finalize())
+}
+
+/// Commits to `sum_k r^k I_k`, where `I_k` interpolates cell `k` over its coset.
+fn compute_commitment_to_aggregated_interpolation_poly(
+    cell_indices: &[u64],
+    cells_fr: &[Vec<Scalar>],
+    r_powers: &[Scalar],
+    s: &KZGSettings,
+) -> Result<G1Point> {
+    let mut aggregated = vec![Scalar::zero(); FIELD_ELEMENTS_PER_EXT_BLOB];
+    let mut used = [false; CELLS_PER_EXT_BLOB];
+
+    // Sum the weighted evaluations of cells sharing an index
+    for ((&index, cell), r) in cell_indices.iter().zip(cells_fr).zip(r_powers) {
+        let start = index as usize * FIELD_ELEMENTS_PER_CELL;
+        for (x, y) in aggregated[start..start + FIELD_ELEMENTS_PER_CELL]
+            .iter_mut()
+            .zip(cell)
+        {
+            *x += *y * *r;
+        }
+        used[index as usize] = true;
+    }
+
+    let mut interpolation = vec![Scalar::zero(); FIELD_ELEMENTS_PER_CELL];
+    for (i, column) in aggregated
+        .chunks_exact_mut(FIELD_ELEMENTS_PER_CELL)
+        .enumerate()
+    {
+        if !used[i] {
+            continue;
+        }
+        // Interpolate over the subgroup, as if the coset shift were one
+        bit_reversal_permutation(column)?;
+        let mut column_coeffs = fr_ifft(column, FIELD_ELEMENTS_PER_CELL, s)?;
+
+        // Then undo the shift: coefficient d is scaled by h^-d
+        let rev = reverse_bits_limited(i, CELLS_PER_EXT_BLOB);
+        shift_poly(&mut column_coeffs, &s.reverse_roots_of_unity[rev]);
+        for (x, c) in interpolation.iter_mut().zip(column_coeffs) {
+            *x += c;
+        }
+    }
+
+    Ok(g1_lincomb_naive(
+        &s.g1_values_monomial[..FIELD_ELEMENTS_PER_CELL],
+        &interpolation,
+    ))
+}
+
+fn verify_cell_kzg_proof_batch_impl(
+    commitments_bytes: &[Bytes48],
+    cell_indices: &[u64],
+    cells: &[Cell],
+    proofs_bytes: &[Bytes48],
+    s: &KZGSettings,
+) -> Result<bool> {
+    // Cells need the monomial form points
+    check!(!s.g1_values_monomial.is_empty());
+
+    if cells.is_empty() {
+        return Ok(true);
+    }
+    for &i in cell_indices {
+        check!(i < CELLS_PER_EXT_BLOB as u64);
+    }
+
+    // Deduplicate the commitments, keeping the order of first appearance
+    let mut unique_commitments: Vec<Bytes48> = Vec::new();
+    let mut commitment_indices = Vec::with_capacity(cells.len());
+    for commitment in commitments_bytes {
+        let j = match unique_commitments.iter().position(|c| c == commitment) {
+            Some(j) => j,
+            None => {
+                unique_commitments.push(*commitment);
+                unique_commitments.len() - 1
+            }
+        };
+        commitment_indices.push(j as u64);
+    }
+
+    // Check the inputs are well-formed
+    let commitments_g1 = unique_commitments
+        .iter()
+        .map(validate_kzg_g1)
+        .collect::<Result<Vec<_>>>()?;
+    let mut proofs_g1 = Vec::with_capacity(cells.len());
+    let mut cells_fr = Vec::with_capacity(cells.len());
+    for (proof, cell) in proofs_bytes.iter().zip(cells) {
+        proofs_g1.push(validate_kzg_g1(proof)?);
+        cells_fr.push(cell_to_fr_array(cell)?);
+    }
+
+    let r = compute_cell_batch_challenge(
+        &unique_commitments,
+        &commitment_indices,
+        cell_indices,
+        cells,
+        proofs_bytes,
+    );
+    let r_powers = compute_powers(&r, cells.len());
+
+    // sum(r^k * proof_k)
+    let proof_lincomb = g1_lincomb_naive(&proofs_g1, &r_powers);
+
+    // sum(weight_i * commitment_i), summing r^k per commitment
+    let mut weights = vec![Scalar::zero(); unique_commitments.len()];
+    for (&j, r) in commitment_indices.iter().zip(&r_powers) {
+        weights[j as usize] += *r;
+    }
+    let rlc = g1_lincomb_naive(&commitments_g1, &weights);
+
+    let rli =
+        compute_commitment_to_aggregated_interpolation_poly(cell_indices, &cells_fr, &r_powers, s)?;
+
+    // sum(r^k * h_k^l * proof_k)
+    let weights: Vec<Scalar> = cell_indices
+        .iter()
+        .zip(&r_powers)
+        .map(|(&i, r)| {
+            let rev = reverse_bits_limited(i as usize, CELLS_PER_EXT_BLOB);
+            *r * s.expanded_roots_of_unity[rev * FIELD_ELEMENTS_PER_CELL]
+        })
+        .collect();
+    let rlp = g1_lincomb_naive(&proofs_g1, &weights);
+
+    // e(proof_lincomb, [tau^l]) == e(rlc - rli + rlp, [1])
+    Ok(pairings_verify(
+        &proof_lincomb,
+        &s.g2_values[FIELD_ELEMENTS_PER_CELL],
+        &(rlc - rli + rlp),
+        &G2Point::generator(),
+    ))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// The C interface
+///////////////////////////////////////////////////////////////////////////////
+
+/// Like `slice::from_raw_parts`, but tolerates a null pointer when `n` is zero.
+unsafe fn raw_slice<'a, T>(p: *const T, n: usize) -> &'a [T] {
+    if n == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(p, n)
+    }
+}
+
+fn into_ret<T>(res: Result<T>, write: impl FnOnce(T)) -> C_KZG_RET {
+    match res {
+        Ok(value) => {
+            write(value);
+            C_KZG_RET::C_KZG_OK
+        }
+        Err(e) => e,
+    }
+}
+
+pub unsafe fn load_trusted_setup(
+    out: *mut KZGSettings,
+    g1_monomial_bytes: *const u8,
+    g1_lagrange_bytes: *const u8,
+    n1: usize,
+    g2_bytes: *const u8,
+    n2: usize,
+) -> C_KZG_RET {
+    // Sanity check in case this is called directly
+    if n1 != FIELD_ELEMENTS_PER_BLOB || n2 != NUM_G2_POINTS {
+        return C_KZG_RET::C_KZG_BADARGS;
+    }
+    let g1_len = n1 * BYTES_PER_COMMITMENT;
+    let g1_monomial = if g1_monomial_bytes.is_null() {
+        None
+    } else {
+        Some(raw_slice(g1_monomial_bytes, g1_len))
+    };
+    let res = load_trusted_setup_impl(
+        g1_monomial,
+        raw_slice(g1_lagrange_bytes, g1_len),
+        n1,
+        raw_slice(g2_bytes, n2 * 96),
+    );
+    into_ret(res, |s| ptr::write(out, s))
+}
+
+pub unsafe fn load_trusted_setup_file(out: *mut KZGSettings, in_: *mut FILE) -> C_KZG_RET {
+    let mut bytes = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = libc::fread(buf.as_mut_ptr().cast(), 1, buf.len(), in_);
+        bytes.extend_from_slice(&buf[..n]);
+        if n < buf.len() {
+            break;
+        }
+    }
+    match KZGSettings::parse_trusted_setup(&bytes) {
+        Ok(s) => {
+            ptr::write(out, s);
+            C_KZG_RET::C_KZG_OK
+        }
+        Err(_) => C_KZG_RET::C_KZG_BADARGS,
+    }
+}
+
+/// The settings own their memory, so this only exists to mirror the C interface.
+pub unsafe fn free_trusted_setup(_s: *mut KZGSettings) {}
+
+/// The monomial g1 points of the setup, compressed.
+#[cfg(all(test, not(feature = "minimal-spec")))]
+pub(crate) fn g1_monomial_bytes(s: &KZGSettings) -> Vec<[u8; super::BYTES_PER_G1_POINT]> {
+    s.g1_values_monomial.iter().map(|p| p.compress()).collect()
+}
+
+pub unsafe fn blob_to_kzg_commitment(
+    out: *mut KZGCommitment,
+    blob: *const Blob,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    let (blob, s) = (&*blob, &*s);
+    let res = blob_to_polynomial(blob).and_then(|p| g1_lincomb_fast(&s.g1_values_affines, &p));
+    into_ret(res, |c| {
+        ptr::write(
+            out,
+            KZGCommitment {
+                bytes: c.compress(),
+            },
+        )
+    })
+}
+
+pub unsafe fn compute_kzg_proof(
+    proof_out: *mut KZGProof,
+    y_out: *mut Bytes32,
+    blob: *const Blob,
+    z_bytes: *const Bytes32,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    let res = blob_to_polynomial(&*blob).and_then(|polynomial| {
+        let z = bytes_to_bls_field(&(*z_bytes).bytes)?;
+        compute_kzg_proof_impl(&polynomial, &z, &*s)
+    });
+    into_ret(res, |(proof, y)| {
+        ptr::write(
+            proof_out,
+            KZGProof {
+                bytes: proof.compress(),
+            },
+        );
+        ptr::write(
+            y_out,
+            Bytes32 {
+                bytes: y.to_bytes_be(),
+            },
+        );
+    })
+}
+
+pub unsafe fn evaluate_blob_at(
+    y_out: *mut Bytes32,
+    blob: *const Blob,
+    z_bytes: *const Bytes32,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    let res = blob_to_polynomial(&*blob).and_then(|polynomial| {
+        let z = bytes_to_bls_field(&(*z_bytes).bytes)?;
+        evaluate_polynomial_in_evaluation_form(&polynomial, &z, &*s)
+    });
+    into_ret(res, |y| {
+        ptr::write(
+            y_out,
+            Bytes32 {
+                bytes: y.to_bytes_be(),
+            },
+        )
+    })
+}
+
+pub unsafe fn compute_blob_kzg_proof(
+    out: *mut KZGProof,
+    blob: *const Blob,
+    commitment_bytes: *const Bytes48,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    let blob = &*blob;
+    let res = validate_kzg_g1(&*commitment_bytes).and_then(|commitment| {
+        let polynomial = blob_to_polynomial(blob)?;
+        let z = compute_challenge(blob, &commitment);
+        compute_kzg_proof_impl(&polynomial, &z, &*s)
+    });
+    into_ret(res, |(proof, _)| {
+        ptr::write(
+            out,
+            KZGProof {
+                bytes: proof.compress(),
+            },
+        )
+    })
+}
+
+pub unsafe fn verify_kzg_proof(
+    ok: *mut bool,
+    commitment_bytes: *const Bytes48,
+    z_bytes: *const Bytes32,
+    y_bytes: *const Bytes32,
+    proof_bytes: *const Bytes48,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    *ok = false;
+    let res = (|| {
+        let commitment = validate_kzg_g1(&*commitment_bytes)?;
+        let z = bytes_to_bls_field(&(*z_bytes).bytes)?;
+        let y = bytes_to_bls_field(&(*y_bytes).bytes)?;
+        let proof = validate_kzg_g1(&*proof_bytes)?;
+        Ok(verify_kzg_proof_impl(&commitment, &z, &y, &proof, &*s))
+    })();
+    into_ret(res, |valid| *ok = valid)
+}
+
+pub unsafe fn verify_blob_kzg_proof(
+    ok: *mut bool,
+    blob: *const Blob,
+    commitment_bytes: *const Bytes48,
+    proof_bytes: *const Bytes48,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    *ok = false;
+    let res = verify_blob_kzg_proof_impl(&*blob, &*commitment_bytes, &*proof_bytes, &*s);
+    into_ret(res, |valid| *ok = valid)
+}
+
+pub unsafe fn verify_blob_kzg_proof_batch(
+    ok: *mut bool,
+    blobs: *const Blob,
+    commitments_bytes: *const Bytes48,
+    proofs_bytes: *const Bytes48,
+    n: usize,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    *ok = false;
+    let res = verify_blob_kzg_proof_batch_impl(
+        raw_slice(blobs, n),
+        raw_slice(commitments_bytes, n),
+        raw_slice(proofs_bytes, n),
+        &*s,
+    );
+    into_ret(res, |valid| *ok = valid)
+}
+
+unsafe fn write_cells_and_proofs(
+    cells_out: *mut Cell,
+    proofs_out: *mut KZGProof,
+    (cells, proofs): (Vec<Cell>, Vec<G1Point>),
+) {
+    for (i, (cell, proof)) in cells.into_iter().zip(proofs).enumerate() {
+        ptr::write(cells_out.add(i), cell);
+        ptr::write(
+            proofs_out.add(i),
+            KZGProof {
+                bytes: proof.compress(),
+            },
+        );
+    }
+}
+
+pub unsafe fn compute_cells_and_kzg_proofs(
+    cells: *mut Cell,
+    proofs: *mut KZGProof,
+    blob: *const Blob,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    let res = compute_cells_and_kzg_proofs_from_blob(&*blob, &*s);
+    into_ret(res, |out| write_cells_and_proofs(cells, proofs, out))
+}
+
+pub unsafe fn blob_to_coefficients(
+    out: *mut Blob,
+    blob: *const Blob,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    into_ret(blob_to_coefficients_impl(&*blob, &*s), |b| {
+        ptr::write(out, b)
+    })
+}
+
+pub unsafe fn coefficients_to_blob(
+    out: *mut Blob,
+    coeffs: *const Blob,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    into_ret(coefficients_to_blob_impl(&*coeffs, &*s), |b| {
+        ptr::write(out, b)
+    })
+}
+
+pub unsafe fn recover_cells_and_kzg_proofs(
+    recovered_cells: *mut Cell,
+    recovered_proofs: *mut KZGProof,
+    cell_indices: *const u64,
+    cells: *const Cell,
+    num_cells: usize,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    let res = recover_cells_and_kzg_proofs_impl(
+        raw_slice(cell_indices, num_cells),
+        raw_slice(cells, num_cells),
+        &*s,
+    );
+    into_ret(res, |out| {
+        write_cells_and_proofs(recovered_cells, recovered_proofs, out)
+    })
+}
+
+pub unsafe fn verify_cell_kzg_proof_batch(
+    ok: *mut bool,
+    commitments_bytes: *const Bytes48,
+    cell_indices: *const u64,
+    cells: *const Cell,
+    proofs_bytes: *const Bytes48,
+    num_cells: usize,
+    s: *const KZGSettings,
+) -> C_KZG_RET {
+    *ok = false;
+    let res = verify_cell_kzg_proof_batch_impl(
+        raw_slice(commitments_bytes, num_cells),
+        raw_slice(cell_indices, num_cells),
+        raw_slice(cells, num_cells),
+        raw_slice(proofs_bytes, num_cells),
+        &*s,
+    );
+    into_ret(res, |valid| *ok = valid)
+}
+
+// These only cover helpers. The backend as a whole runs through the tests in the parent module
+// and the vectors in `tests/`, all of which were computed by the C backend. Passing them shows
+// that the two ports agree; no independent reference is involved until the upstream vectors are
+// vendored.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_reversal_permutation() {
+        let mut values: Vec<usize> = (0..16).collect();
+        bit_reversal_permutation(&mut values).unwrap();
+        assert_eq!(
+            values,
+            [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15]
+        );
+        assert!(bit_reversal_permutation(&mut [0u8; 12]).is_err());
+    }
+
+    #[test]
+    fn test_fr_batch_inv() {
+        let a: Vec<Scalar> = (1..10).map(Scalar::from_u64).collect();
+        for (x, inv) in a.iter().zip(fr_batch_inv(&a).unwrap()) {
+            assert_eq!(*x * inv, Scalar::one());
+        }
+        assert!(fr_batch_inv(&[Scalar::one(), Scalar::zero()]).is_err());
+    }
+
+    #[test]
+    fn test_roots_of_unity() {
+        let (roots, expanded, reverse) = compute_roots_of_unity(16).unwrap();
+        assert_eq!(roots[1], expanded[8]);
+        for i in 0..=16 {
+            assert_eq!(expanded[i] * reverse[i], Scalar::one());
+        }
+    }
+}
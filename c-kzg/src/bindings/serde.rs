@@ -1,6 +1,6 @@
 //! Serde serialization and deserialization for the basic types in this crate.
 
-use crate::{Blob, Bytes32, Bytes48};
+use crate::{Blob, Bytes32, Bytes48, KzgCommitment, KzgProof};
 use alloc::string::String;
 use alloc::vec::Vec;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
@@ -47,6 +47,30 @@ impl<'de> Deserialize<'de> for Bytes48 {
     }
 }
 
+impl Serialize for KzgCommitment {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.bytes, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KzgCommitment {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        KzgCommitment::from_bytes(&deserialize_hex(deserializer)?).map_err(Error::custom)
+    }
+}
+
+impl Serialize for KzgProof {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.bytes, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KzgProof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        KzgProof::from_bytes(&deserialize_hex(deserializer)?).map_err(Error::custom)
+    }
+}
+
 impl Serialize for Bytes32 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -81,8 +105,14 @@ mod tests {
         arr.into()
     }
 
+    #[cfg(not(feature = "minimal-spec"))]
+    fn trusted_setup_file() -> &'static Path {
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src/trusted_setup.txt"))
+    }
+
+    #[cfg(feature = "minimal-spec")]
     fn trusted_setup_file() -> &'static Path {
-        Path::new("../../src/trusted_setup.txt")
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src/trusted_setup_4.txt"))
     }
 
     #[test]
@@ -0,0 +1,92 @@
+//! SSZ encoding and merkleization for the basic types in this crate.
+//!
+//! All of them are SSZ byte vectors: they encode as their raw bytes and their hash tree root is
+//! the merkleization of those bytes packed into 32-byte chunks.
+
+use crate::{Blob, Bytes32, Bytes48, KzgCommitment, KzgProof};
+use alloc::vec::Vec;
+use ssz::{Codec, Decode, Encode, Error, HashTree, Hasher, Size};
+
+/// The SSZ size of a byte vector of length `N`.
+pub struct ByteVectorSize<const N: usize>;
+
+impl<const N: usize> Size for ByteVectorSize<N> {
+    fn size() -> Option<usize> {
+        Some(N)
+    }
+}
+
+// Decoding goes through the type's own `from_bytes`, which only checks the length: like the serde
+// impls, commitments and proofs are not validated as points until they are used.
+macro_rules! ssz_byte_vector {
+    ($t:ty, $len:expr) => {
+        impl Codec for $t {
+            type Size = ByteVectorSize<{ $len }>;
+        }
+
+        impl Encode for $t {
+            fn encode(&self) -> Vec<u8> {
+                self.bytes.to_vec()
+            }
+
+            fn using_encoded<R, F: FnOnce(&[u8]) -> R>(&self, f: F) -> R {
+                f(&self.bytes)
+            }
+        }
+
+        impl Decode for $t {
+            fn decode(value: &[u8]) -> Result<Self, Error> {
+                <$t>::from_bytes(value).map_err(|_| Error::IncorrectSize)
+            }
+        }
+
+        impl HashTree for $t {
+            fn hash_tree_root_with(&self, h: &mut Hasher) -> Result<(), Error> {
+                h.put_bytes(&self.bytes);
+                Ok(())
+            }
+        }
+    };
+}
+
+ssz_byte_vector!(Blob, crate::BYTES_PER_BLOB);
+ssz_byte_vector!(Bytes32, 32);
+ssz_byte_vector!(Bytes48, 48);
+ssz_byte_vector!(KzgCommitment, crate::BYTES_PER_COMMITMENT);
+ssz_byte_vector!(KzgProof, crate::BYTES_PER_PROOF);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::hash_root;
+
+    #[test]
+    fn test_ssz_roundtrip() {
+        let mut bytes = [0u8; 48];
+        bytes[0] = 0xc0;
+        let commitment = KzgCommitment::from(bytes);
+        let encoded = commitment.encode();
+        assert_eq!(encoded, bytes.to_vec());
+        assert_eq!(KzgCommitment::decode(&encoded).unwrap(), commitment);
+        assert!(KzgCommitment::decode(&encoded[1..]).is_err());
+        assert_eq!(<<KzgCommitment as Codec>::Size as Size>::size(), Some(48));
+
+        let blob = Blob::new([1u8; crate::BYTES_PER_BLOB]);
+        assert_eq!(Blob::decode(&blob.encode()).unwrap(), blob);
+    }
+
+    #[test]
+    fn test_hash_tree_root() {
+        // A 32-byte vector is its own root; a 48-byte vector is the hash of its two chunks.
+        let root = [7u8; 32];
+        assert_eq!(hash_root(&Bytes32::from(root)).unwrap(), root);
+
+        let bytes = [7u8; 48];
+        let mut chunks = [0u8; 64];
+        chunks[..48].copy_from_slice(&bytes);
+        assert_eq!(
+            hash_root(&Bytes48::from(bytes)).unwrap(),
+            blst::sha256_sum(&chunks)
+        );
+    }
+}
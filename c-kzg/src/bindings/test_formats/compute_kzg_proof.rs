@@ -1,12 +1,12 @@
 #![allow(dead_code)]
 
-use crate::{Blob, Bytes48, Error};
+use crate::{Blob, Bytes32, Bytes48, Error};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct Input<'a> {
     blob: &'a str,
-    commitment: &'a str,
+    z: &'a str,
 }
 
 impl Input<'_> {
@@ -14,8 +14,8 @@ impl Input<'_> {
         Blob::from_hex(self.blob)
     }
 
-    pub fn get_commitment(&self) -> Result<Bytes48, Error> {
-        Bytes48::from_hex(self.commitment)
+    pub fn get_z(&self) -> Result<Bytes32, Error> {
+        Bytes32::from_hex(self.z)
     }
 }
 
@@ -24,11 +24,16 @@ pub struct Test<'a> {
     #[serde(borrow)]
     pub input: Input<'a>,
     #[serde(borrow)]
-    output: Option<&'a str>,
+    output: Option<(&'a str, &'a str)>,
 }
 
 impl Test<'_> {
-    pub fn get_output(&self) -> Option<Bytes48> {
-        self.output.map(|s| Bytes48::from_hex(s).unwrap())
+    pub fn get_output(&self) -> Option<(Bytes48, Bytes32)> {
+        self.output.map(|(proof, y)| {
+            (
+                Bytes48::from_hex(proof).unwrap(),
+                Bytes32::from_hex(y).unwrap(),
+            )
+        })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,159 @@
+//! Packing of arbitrary payloads into blobs.
+//!
+//! The payload is prefixed with its length as a big-endian `u64` and the result is split into
+//! 31-byte chunks. Each chunk fills the low 31 bytes of a field element whose top byte is zero,
+//! so every element is below the BLS modulus and the blobs are always accepted by
+//! `blob_to_kzg_commitment`. The last blob is zero-padded.
+//!
+//! Decoding is strict: the top byte of every element, the padding after the payload and the
+//! number of blobs are all checked, so each payload has exactly one encoding.
+
+use crate::{Blob, Error, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT, FIELD_ELEMENTS_PER_BLOB};
+use alloc::vec::Vec;
+
+/// Payload bytes carried by one field element.
+pub const USABLE_BYTES_PER_FIELD_ELEMENT: usize = BYTES_PER_FIELD_ELEMENT - 1;
+
+/// Payload bytes carried by one blob, including the length prefix.
+pub const USABLE_BYTES_PER_BLOB: usize = USABLE_BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_BLOB;
+
+const LENGTH_PREFIX_BYTES: usize = 8;
+
+/// Returns the number of blobs [`encode_blobs`] produces for a payload of `len` bytes.
+pub fn blobs_needed(len: usize) -> usize {
+    // The length prefix makes the stream non-empty.
+    (LENGTH_PREFIX_BYTES + len - 1) / USABLE_BYTES_PER_BLOB + 1
+}
+
+/// Packs `data` into as few blobs as possible. An empty payload still takes one blob.
+pub fn encode_blobs(data: &[u8]) -> Vec<Blob> {
+    let mut stream = Vec::with_capacity(LENGTH_PREFIX_BYTES + data.len());
+    stream.extend_from_slice(&(data.len() as u64).to_be_bytes());
+    stream.extend_from_slice(data);
+
+    stream
+        .chunks(USABLE_BYTES_PER_BLOB)
+        .map(|chunk| {
+            let mut bytes = [0u8; BYTES_PER_BLOB];
+            for (element, src) in bytes
+                .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+                .zip(chunk.chunks(USABLE_BYTES_PER_FIELD_ELEMENT))
+            {
+                element[1..1 + src.len()].copy_from_slice(src);
+            }
+            Blob::new(bytes)
+        })
+        .collect()
+}
+
+/// Recovers the payload packed by [`encode_blobs`].
+pub fn decode_blobs(blobs: &[Blob]) -> Result<Vec<u8>, Error> {
+    if blobs.is_empty() {
+        return Err(Error::InvalidBlobEncoding("No blobs to decode".into()));
+    }
+
+    let mut stream = Vec::with_capacity(blobs.len() * USABLE_BYTES_PER_BLOB);
+    for (i, blob) in blobs.iter().enumerate() {
+        for (j, element) in blob.chunks_exact(BYTES_PER_FIELD_ELEMENT).enumerate() {
+            if element[0] != 0 {
+                return Err(Error::InvalidBlobEncoding(format!(
+                    "Field element {} of blob {} has a non-zero top byte",
+                    j, i
+                )));
+            }
+            stream.extend_from_slice(&element[1..]);
+        }
+    }
+
+    let mut prefix = [0u8; LENGTH_PREFIX_BYTES];
+    prefix.copy_from_slice(&stream[..LENGTH_PREFIX_BYTES]);
+    let len = u64::from_be_bytes(prefix);
+    let capacity = stream.len() - LENGTH_PREFIX_BYTES;
+    if len > capacity as u64 {
+        return Err(Error::InvalidBlobEncoding(format!(
+            "Payload length {} exceeds the capacity {} of {} blobs",
+            len,
+            capacity,
+            blobs.len()
+        )));
+    }
+    let len = len as usize;
+    if blobs_needed(len) != blobs.len() {
+        return Err(Error::InvalidBlobEncoding(format!(
+            "A payload of {} bytes takes {} blobs, got {}",
+            len,
+            blobs_needed(len),
+            blobs.len()
+        )));
+    }
+
+    let end = LENGTH_PREFIX_BYTES + len;
+    if stream[end..].iter().any(|&b| b != 0) {
+        return Err(Error::InvalidBlobEncoding(
+            "Non-zero padding after the payload".into(),
+        ));
+    }
+    stream.truncate(end);
+    stream.drain(..LENGTH_PREFIX_BYTES);
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + 3) as u8 | 0x80).collect()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let max_single = USABLE_BYTES_PER_BLOB - LENGTH_PREFIX_BYTES;
+        for len in [
+            0,
+            1,
+            23,
+            31,
+            32,
+            1000,
+            max_single,
+            max_single + 1,
+            3 * USABLE_BYTES_PER_BLOB,
+        ] {
+            let data = payload(len);
+            let blobs = encode_blobs(&data);
+            assert_eq!(blobs.len(), blobs_needed(len));
+            for blob in &blobs {
+                assert!(blob
+                    .chunks_exact(BYTES_PER_FIELD_ELEMENT)
+                    .all(|e| e[0] == 0));
+            }
+            assert_eq!(decode_blobs(&blobs).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_non_canonical() {
+        // Small enough to fit in one blob under either preset. The blob is edited in place, as
+        // copies of a mainnet blob would overflow the stack of a debug test thread.
+        let mut blobs = encode_blobs(&payload(20));
+        assert_eq!(blobs.len(), 1);
+        let original = blobs[0].to_vec();
+
+        let mut rejects = |edit: &dyn Fn(&mut [u8])| {
+            blobs[0].copy_from_slice(&original);
+            edit(&mut blobs[0][..]);
+            assert!(decode_blobs(&blobs).is_err());
+        };
+        rejects(&|bytes| bytes[BYTES_PER_FIELD_ELEMENT * (FIELD_ELEMENTS_PER_BLOB - 1)] = 1);
+        rejects(&|bytes| bytes[BYTES_PER_BLOB - 1] = 1);
+        rejects(&|bytes| bytes[1..9].copy_from_slice(&u64::MAX.to_be_bytes()));
+
+        blobs[0].copy_from_slice(&original);
+        assert!(decode_blobs(&blobs).is_ok());
+        // An all-zero blob too many.
+        blobs.extend(encode_blobs(&[]));
+        assert!(decode_blobs(&blobs).is_err());
+        assert!(decode_blobs(&[]).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Elements of the BLS12-381 scalar field and conversions between blobs and field elements.
+
+use crate::bindings;
+use crate::{
+    Blob, Bytes32, CkzgError, Error, KzgSettings, BYTES_PER_BLOB, BYTES_PER_FIELD_ELEMENT,
+    FIELD_ELEMENTS_PER_BLOB,
+};
+use alloc::vec::Vec;
+use blst::points::Scalar;
+use core::mem::MaybeUninit;
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// An element of the BLS12-381 scalar field, the field blobs are made of.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Fr(Scalar);
+
+impl Fr {
+    pub fn zero() -> Self {
+        Self(Scalar::zero())
+    }
+
+    pub fn one() -> Self {
+        Self(Scalar::one())
+    }
+
+    pub fn from_u64(v: u64) -> Self {
+        Self(Scalar::from_u64(v))
+    }
+
+    /// Parses a canonical big-endian field element, rejecting values not below the modulus.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != BYTES_PER_FIELD_ELEMENT {
+            return Err(Error::InvalidBytesLength(format!(
+                "Invalid byte length. Expected {} got {}",
+                BYTES_PER_FIELD_ELEMENT,
+                bytes.len(),
+            )));
+        }
+        Scalar::from_bytes_be(bytes).map(Self).map_err(|_| {
+            Error::InvalidFieldElement(format!(
+                "0x{} is not below the BLS modulus",
+                hex::encode(bytes)
+            ))
+        })
+    }
+
+    /// Interprets `bytes` as a big-endian integer of any length and reduces it modulo the BLS
+    /// modulus.
+    pub fn from_bytes_reduced(bytes: &[u8]) -> Self {
+        Self(Scalar::from_bytes_be_reduced(bytes))
+    }
+
+    pub fn to_bytes(&self) -> Bytes32 {
+        Bytes32::from(self.0.to_bytes_be())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// Returns `None` for zero.
+    pub fn inverse(&self) -> Option<Self> {
+        self.0.inverse().map(Self)
+    }
+
+    pub fn pow(&self, mut n: u64) -> Self {
+        let mut base = self.0;
+        let mut out = Scalar::one();
+        while n > 0 {
+            if n & 1 == 1 {
+                out *= base;
+            }
+            base = base.square();
+            n >>= 1;
+        }
+        Self(out)
+    }
+}
+
+impl From<Scalar> for Fr {
+    fn from(value: Scalar) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Fr> for Scalar {
+    fn from(value: Fr) -> Self {
+        value.0
+    }
+}
+
+macro_rules! fr_binop {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait for Fr {
+            type Output = Self;
+
+            fn $method(self, other: Self) -> Self {
+                Self($trait::$method(self.0, other.0))
+            }
+        }
+
+        impl $assign_trait for Fr {
+            fn $assign_method(&mut self, other: Self) {
+                $assign_trait::$assign_method(&mut self.0, other.0)
+            }
+        }
+    };
+}
+
+fr_binop!(Add, add, AddAssign, add_assign);
+fr_binop!(Sub, sub, SubAssign, sub_assign);
+fr_binop!(Mul, mul, MulAssign, mul_assign);
+
+impl Neg for Fr {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+/// Evaluates the polynomial represented by `blob` at `z`, which need not be in the blob domain.
+pub fn evaluate_blob_at(blob: &Blob, z: &Fr, kzg_settings: &KzgSettings) -> Result<Fr, Error> {
+    let z_bytes = z.to_bytes();
+    let mut y_out = MaybeUninit::<Bytes32>::uninit();
+    unsafe {
+        let res = bindings::evaluate_blob_at(y_out.as_mut_ptr(), blob, &z_bytes, kzg_settings);
+        if let CkzgError::C_KZG_OK = res {
+            Fr::from_bytes(&*y_out.assume_init())
+        } else {
+            Err(Error::CError(res))
+        }
+    }
+}
+
+/// Checks that every field element of `blob` is canonical, which is what the KZG functions
+/// require of their inputs.
+pub fn validate_blob(blob: &Blob) -> Result<(), Error> {
+    blob_to_field_elements(blob).map(|_| ())
+}
+
+/// Splits `blob` into its field elements.
+pub fn blob_to_field_elements(blob: &Blob) -> Result<Vec<Fr>, Error> {
+    blob.chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .enumerate()
+        .map(|(i, bytes)| {
+            Fr::from_bytes(bytes).map_err(|_| {
+                Error::InvalidFieldElement(format!(
+                    "Field element {} of the blob is not below the BLS modulus",
+                    i
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Packs field elements into a blob; there must be exactly `FIELD_ELEMENTS_PER_BLOB` of them.
+pub fn field_elements_to_blob(elements: &[Fr]) -> Result<Blob, Error> {
+    if elements.len() != FIELD_ELEMENTS_PER_BLOB {
+        return Err(Error::MismatchLength(format!(
+            "A blob holds {} field elements, got {}",
+            FIELD_ELEMENTS_PER_BLOB,
+            elements.len()
+        )));
+    }
+    let mut bytes = [0u8; BYTES_PER_BLOB];
+    for (out, x) in bytes
+        .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+        .zip(elements)
+    {
+        out.copy_from_slice(&*x.to_bytes());
+    }
+    Ok(Blob::new(bytes))
+}
+
+/// Reduces every field element of `blob` modulo the BLS modulus, so that the result is accepted
+/// by the KZG functions. Canonical elements are left unchanged.
+pub fn reduce_blob(blob: &Blob) -> Blob {
+    let mut bytes = [0u8; BYTES_PER_BLOB];
+    for (out, x) in bytes
+        .chunks_exact_mut(BYTES_PER_FIELD_ELEMENT)
+        .zip(blob.chunks_exact(BYTES_PER_FIELD_ELEMENT))
+    {
+        out.copy_from_slice(&*Fr::from_bytes_reduced(x).to_bytes());
+    }
+    Blob::new(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fr_arithmetic() {
+        let a = Fr::from_u64(6);
+        let b = Fr::from_u64(4);
+        assert_eq!(a + b, Fr::from_u64(10));
+        assert_eq!(a - b, Fr::from_u64(2));
+        assert_eq!(a * b, Fr::from_u64(24));
+        assert_eq!(b - a + Fr::from_u64(2), Fr::zero());
+        assert_eq!(-a + a, Fr::zero());
+        assert_eq!(a * a.inverse().unwrap(), Fr::one());
+        assert_eq!(Fr::zero().inverse(), None);
+        assert_eq!(Fr::from_u64(3).pow(5), Fr::from_u64(243));
+    }
+
+    #[test]
+    fn test_fr_bytes() {
+        // r - 1 is canonical, r is not and reduces to zero.
+        let r_minus_1 =
+            hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000")
+                .unwrap();
+        let r = hex::decode("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001")
+            .unwrap();
+        assert_eq!(Fr::from_bytes(&r_minus_1).unwrap(), -Fr::one());
+        assert!(Fr::from_bytes(&r).is_err());
+        assert!(Fr::from_bytes(&r[1..]).is_err());
+        assert_eq!(Fr::from_bytes_reduced(&r), Fr::zero());
+        assert_eq!(*Fr::from_u64(7).to_bytes(), {
+            let mut bytes = [0u8; 32];
+            bytes[31] = 7;
+            bytes
+        });
+    }
+
+    #[test]
+    fn test_blob_field_elements() {
+        let elements: Vec<Fr> = (0..FIELD_ELEMENTS_PER_BLOB)
+            .map(|i| Fr::from_u64(i as u64))
+            .collect();
+        let blob = field_elements_to_blob(&elements).unwrap();
+        assert_eq!(blob_to_field_elements(&blob).unwrap(), elements);
+        assert!(field_elements_to_blob(&elements[1..]).is_err());
+
+        let mut bytes = *blob;
+        bytes[BYTES_PER_FIELD_ELEMENT] = 0xff;
+        let blob = Blob::new(bytes);
+        assert!(validate_blob(&blob).is_err());
+        let reduced = reduce_blob(&blob);
+        validate_blob(&reduced).unwrap();
+        assert_eq!(
+            reduced[..BYTES_PER_FIELD_ELEMENT],
+            blob[..BYTES_PER_FIELD_ELEMENT]
+        );
+    }
+}
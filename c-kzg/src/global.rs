@@ -0,0 +1,117 @@
+//! The process-wide trusted setup.
+//!
+//! The global setup is fixed the first time it is needed: either explicitly with
+//! [`install_trusted_setup`], typically at startup, or implicitly by [`trusted_setup`], which falls
+//! back to the setup bundled with this crate. Once fixed it lives for the rest of the process, so
+//! references to it are `'static`.
+
+use crate::{build_setting, Error, KzgSettings, BUILDIN_TRUSTED_SETUP};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+/// Where the global trusted setup came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrustedSetupSource {
+    /// The mainnet setup bundled with this crate.
+    Builtin,
+    /// A setup installed with [`install_trusted_setup`], under the name it was given.
+    Custom(String),
+}
+
+struct GlobalSetup {
+    settings: KzgSettings,
+    source: TrustedSetupSource,
+}
+
+static GLOBAL_SETUP: AtomicPtr<GlobalSetup> = AtomicPtr::new(ptr::null_mut());
+
+/// Publishes `setup` unless another one got there first, and returns whichever is installed.
+fn install(setup: GlobalSetup) -> Result<&'static GlobalSetup, &'static GlobalSetup> {
+    let new = Box::into_raw(Box::new(setup));
+    match GLOBAL_SETUP.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire) {
+        // The setup is never freed once published.
+        Ok(_) => Ok(unsafe { &*new }),
+        Err(current) => {
+            drop(unsafe { Box::from_raw(new) });
+            Err(unsafe { &*current })
+        }
+    }
+}
+
+fn current() -> Option<&'static GlobalSetup> {
+    let current = GLOBAL_SETUP.load(Ordering::Acquire);
+    if current.is_null() {
+        None
+    } else {
+        Some(unsafe { &*current })
+    }
+}
+
+/// Installs `settings` as the global trusted setup, labelled `name`.
+///
+/// This must happen before the global setup is first used: it fails if a setup, including the
+/// bundled one, is already in place.
+pub fn install_trusted_setup(settings: KzgSettings, name: &str) -> Result<(), Error> {
+    let setup = GlobalSetup {
+        settings,
+        source: TrustedSetupSource::Custom(name.to_string()),
+    };
+    install(setup).map(|_| ()).map_err(|current| {
+        Error::TrustedSetupAlreadyInstalled(format!(
+            "Cannot install trusted setup {:?}: {:?} is already in use",
+            name, current.source
+        ))
+    })
+}
+
+/// Returns the global trusted setup, loading the bundled one if none has been installed.
+///
+/// If the bundled setup fails to load the error is returned and nothing is installed. This is
+/// always the case under the `minimal-spec` preset, where the bundled mainnet setup does not fit.
+pub fn trusted_setup() -> Result<&'static KzgSettings, Error> {
+    if let Some(setup) = current() {
+        return Ok(&setup.settings);
+    }
+    let setup = GlobalSetup {
+        settings: build_setting(BUILDIN_TRUSTED_SETUP)?,
+        source: TrustedSetupSource::Builtin,
+    };
+    // Concurrent first calls may each load the bundled setup; only one of them is kept.
+    let setup = install(setup).unwrap_or_else(|current| current);
+    Ok(&setup.settings)
+}
+
+/// Returns where the global trusted setup came from, or `None` if it has not been fixed yet.
+pub fn trusted_setup_source() -> Option<TrustedSetupSource> {
+    current().map(|setup| setup.source.clone())
+}
+
+// The tests need the bundled setup, which only fits the mainnet preset.
+#[cfg(test)]
+#[cfg(not(feature = "minimal-spec"))]
+mod tests {
+    use super::*;
+
+    // The global setup can only be fixed once per process, so this is the only test touching it.
+    #[test]
+    fn test_install_trusted_setup() {
+        assert_eq!(trusted_setup_source(), None);
+        let settings = build_setting(BUILDIN_TRUSTED_SETUP).unwrap();
+        install_trusted_setup(settings, "devnet").unwrap();
+        assert_eq!(
+            trusted_setup_source(),
+            Some(TrustedSetupSource::Custom("devnet".into()))
+        );
+
+        let first = trusted_setup().unwrap() as *const KzgSettings;
+        assert_eq!(trusted_setup().unwrap() as *const KzgSettings, first);
+
+        let settings = build_setting(BUILDIN_TRUSTED_SETUP).unwrap();
+        assert!(matches!(
+            install_trusted_setup(settings, "other"),
+            Err(Error::TrustedSetupAlreadyInstalled(_))
+        ));
+    }
+}
@@ -0,0 +1,262 @@
+//! Verification of the `kzg_commitment_inclusion_proof` carried by blob sidecars.
+//!
+//! The proof is the Merkle branch from the commitment's hash tree root up to the beacon block
+//! body root, through the `blob_kzg_commitments` list and the body container.
+
+use crate::{Bytes32, Bytes48, Error};
+use blst::sha256::Sha256;
+
+/// Position of `blob_kzg_commitments` in `BeaconBlockBody`.
+const BLOB_KZG_COMMITMENTS_INDEX: usize = 11;
+
+/// Depth of the `BeaconBlockBody` container tree.
+const BEACON_BLOCK_BODY_DEPTH: usize = 4;
+
+/// A consensus fork whose blocks carry blob sidecars.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Fork {
+    Deneb,
+    Electra,
+}
+
+impl Fork {
+    /// Capacity of the `blob_kzg_commitments` list in the block body.
+    #[cfg(not(feature = "minimal-spec"))]
+    pub const fn max_blob_commitments_per_block(self) -> usize {
+        match self {
+            Fork::Deneb | Fork::Electra => 4096,
+        }
+    }
+
+    /// Capacity of the `blob_kzg_commitments` list in the block body.
+    #[cfg(feature = "minimal-spec")]
+    pub const fn max_blob_commitments_per_block(self) -> usize {
+        match self {
+            Fork::Deneb | Fork::Electra => 32,
+        }
+    }
+
+    /// Number of nodes in a `kzg_commitment_inclusion_proof`: the body tree, the length mix-in
+    /// of the commitments list and the list's own tree.
+    pub const fn kzg_commitment_inclusion_proof_depth(self) -> usize {
+        let list_depth = self.max_blob_commitments_per_block().trailing_zeros() as usize;
+        BEACON_BLOCK_BODY_DEPTH + 1 + list_depth
+    }
+
+    /// The generalized index of commitment `index` within the body, relative to the body root.
+    fn kzg_commitment_subtree_index(self, index: usize) -> usize {
+        let list_depth = self.max_blob_commitments_per_block().trailing_zeros() as usize;
+        // The commitments sit under the data (left) child of the list root.
+        (BLOB_KZG_COMMITMENTS_INDEX << (list_depth + 1)) | index
+    }
+}
+
+/// Checks that `proof` places `commitment` at position `index` of the `blob_kzg_commitments` of
+/// the block body with root `body_root`.
+///
+/// Returns `Ok(false)` if the branch does not lead to `body_root`, and an error if `index` is out
+/// of range for `fork` or `proof` has the wrong length.
+pub fn verify_kzg_commitment_inclusion_proof(
+    fork: Fork,
+    commitment: &Bytes48,
+    index: usize,
+    proof: &[Bytes32],
+    body_root: &Bytes32,
+) -> Result<bool, Error> {
+    let depth = fork.kzg_commitment_inclusion_proof_depth();
+    if proof.len() != depth {
+        return Err(Error::MismatchLength(format!(
+            "Expected an inclusion proof of {} nodes, got {}",
+            depth,
+            proof.len()
+        )));
+    }
+    if index >= fork.max_blob_commitments_per_block() {
+        return Err(Error::InvalidBlobSidecar {
+            index,
+            reason: format!(
+                "index exceeds the {} commitments allowed per block",
+                fork.max_blob_commitments_per_block()
+            ),
+        });
+    }
+
+    let mut value = ssz::hash_root(commitment).map_err(|e| {
+        Error::InvalidKzgCommitment(format!("Failed to hash the commitment: {:?}", e))
+    })?;
+    let subtree_index = fork.kzg_commitment_subtree_index(index);
+    for (i, node) in proof.iter().enumerate() {
+        let mut h = Sha256::new();
+        if (subtree_index >> i) & 1 == 1 {
+            h.update(&node[..]);
+            h.update(&value);
+        } else {
+            h.update(&value);
+            h.update(&node[..]);
+        }
+        value = h.finalize();
+    }
+    Ok(value == **body_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let mut h = Sha256::new();
+        h.update(a);
+        h.update(b);
+        h.finalize()
+    }
+
+    /// Builds a block body with two commitments and returns its root and the branch for the
+    /// commitment at index 1.
+    fn body_with_commitments(commitments: &[Bytes48; 2]) -> ([u8; 32], Vec<Bytes32>) {
+        let leaves: Vec<[u8; 32]> = commitments
+            .iter()
+            .map(|c| ssz::hash_root(c).unwrap())
+            .collect();
+        let mut branch = vec![leaves[0]];
+
+        // Commitments list: all but the first two leaves are zero.
+        let list_depth = Fork::Deneb
+            .max_blob_commitments_per_block()
+            .trailing_zeros();
+        let mut zero = [0u8; 32];
+        let mut data_root = hash(&leaves[0], &leaves[1]);
+        for _ in 1..list_depth {
+            zero = hash(&zero, &zero);
+            branch.push(zero);
+            data_root = hash(&data_root, &zero);
+        }
+        let mut length = [0u8; 32];
+        length[0] = 2;
+        branch.push(length);
+        let list_root = hash(&data_root, &length);
+
+        // Body: 16 leaves with the list at position 11.
+        let mut fields: Vec<[u8; 32]> = (0..16u8).map(|i| [i; 32]).collect();
+        fields[BLOB_KZG_COMMITMENTS_INDEX] = list_root;
+        let mut position = BLOB_KZG_COMMITMENTS_INDEX;
+        while fields.len() > 1 {
+            branch.push(fields[position ^ 1]);
+            fields = fields.chunks(2).map(|p| hash(&p[0], &p[1])).collect();
+            position /= 2;
+        }
+        (fields[0], branch.into_iter().map(Bytes32::from).collect())
+    }
+
+    #[test]
+    fn test_verify_kzg_commitment_inclusion_proof() {
+        let commitments = [Bytes48::from([0xa0; 48]), Bytes48::from([0xb0; 48])];
+        let (root, proof) = body_with_commitments(&commitments);
+        let root = Bytes32::from(root);
+        assert_eq!(
+            proof.len(),
+            Fork::Deneb.kzg_commitment_inclusion_proof_depth()
+        );
+        #[cfg(not(feature = "minimal-spec"))]
+        assert_eq!(proof.len(), 17);
+        #[cfg(feature = "minimal-spec")]
+        assert_eq!(proof.len(), 10);
+
+        for fork in [Fork::Deneb, Fork::Electra] {
+            assert!(
+                verify_kzg_commitment_inclusion_proof(fork, &commitments[1], 1, &proof, &root)
+                    .unwrap()
+            );
+            assert!(!verify_kzg_commitment_inclusion_proof(
+                fork,
+                &commitments[0],
+                1,
+                &proof,
+                &root
+            )
+            .unwrap());
+            assert!(!verify_kzg_commitment_inclusion_proof(
+                fork,
+                &commitments[1],
+                0,
+                &proof,
+                &root
+            )
+            .unwrap());
+        }
+
+        let short = &proof[1..];
+        assert!(verify_kzg_commitment_inclusion_proof(
+            Fork::Deneb,
+            &commitments[1],
+            1,
+            short,
+            &root
+        )
+        .is_err());
+        assert!(verify_kzg_commitment_inclusion_proof(
+            Fork::Deneb,
+            &commitments[1],
+            Fork::Deneb.max_blob_commitments_per_block(),
+            &proof,
+            &root
+        )
+        .is_err());
+    }
+
+    fn yaml_index(value: &serde_yaml::Value) -> usize {
+        match value.as_u64() {
+            Some(index) => index as usize,
+            None => value.as_str().unwrap().parse().unwrap(),
+        }
+    }
+
+    // The test above builds its branch with the same index arithmetic as the verifier, so a
+    // shared mistake would pass it. This one checks real Deneb sidecars, none of which are
+    // vendored yet; see `tests/inclusion/README.md`.
+    #[cfg(not(feature = "minimal-spec"))]
+    #[test]
+    #[ignore = "no real Deneb blob sidecars are vendored yet"]
+    fn test_real_deneb_sidecars() {
+        let pattern = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/inclusion/deneb/*");
+        let mut checked = 0;
+        for path in glob::glob(pattern).unwrap().map(Result::unwrap) {
+            // JSON is valid YAML, so this reads both formats. A file may hold one sidecar or a
+            // list of them.
+            let file: serde_yaml::Value =
+                serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            let sidecars = match file {
+                serde_yaml::Value::Sequence(sidecars) => sidecars,
+                sidecar => vec![sidecar],
+            };
+            for sidecar in sidecars {
+                let body_root = sidecar["signed_block_header"]["message"]["body_root"]
+                    .as_str()
+                    .unwrap();
+                let proof: Vec<Bytes32> = sidecar["kzg_commitment_inclusion_proof"]
+                    .as_sequence()
+                    .unwrap()
+                    .iter()
+                    .map(|node| Bytes32::from_hex(node.as_str().unwrap()).unwrap())
+                    .collect();
+                let commitment =
+                    Bytes48::from_hex(sidecar["kzg_commitment"].as_str().unwrap()).unwrap();
+                let index = yaml_index(&sidecar["index"]);
+                assert!(
+                    verify_kzg_commitment_inclusion_proof(
+                        Fork::Deneb,
+                        &commitment,
+                        index,
+                        &proof,
+                        &Bytes32::from_hex(body_root).unwrap()
+                    )
+                    .unwrap(),
+                    "{}: sidecar {}",
+                    path.display(),
+                    index
+                );
+                checked += 1;
+            }
+        }
+        assert!(checked > 0, "no sidecars under tests/inclusion/deneb");
+    }
+}
@@ -10,7 +10,15 @@ extern crate alloc;
 extern crate blst;
 
 mod bindings;
+mod codec;
+mod fr;
+mod global;
+#[cfg(feature = "ssz")]
+mod inclusion;
 
+use alloc::string::String;
+use alloc::vec::Vec;
+use blst::points::{G1Point, G2Point};
 use core::mem::MaybeUninit;
 
 use bindings::NUM_G2_POINTS;
@@ -22,81 +30,248 @@ pub use bindings::{
 // Expose the constants.
 pub use bindings::{
     BYTES_PER_BLOB, BYTES_PER_COMMITMENT, BYTES_PER_FIELD_ELEMENT, BYTES_PER_G1_POINT,
-    BYTES_PER_G2_POINT, BYTES_PER_PROOF, FIELD_ELEMENTS_PER_BLOB,
+    BYTES_PER_CELL, BYTES_PER_G2_POINT, BYTES_PER_PROOF, CELLS_PER_EXT_BLOB,
+    FIELD_ELEMENTS_PER_BLOB, FIELD_ELEMENTS_PER_CELL, FIELD_ELEMENTS_PER_EXT_BLOB,
+    VERSIONED_HASH_VERSION_KZG,
 };
 // Expose the remaining relevant types.
-pub use bindings::{Blob, Bytes32, Bytes48, Error};
+pub use bindings::{validate_blob_sidecar, Blob, Bytes32, Bytes48, Cell, Error};
+// Packing of arbitrary payloads into blobs.
+pub use codec::{
+    blobs_needed, decode_blobs, encode_blobs, USABLE_BYTES_PER_BLOB,
+    USABLE_BYTES_PER_FIELD_ELEMENT,
+};
+// BLS scalar field elements and polynomial evaluation.
+pub use fr::{
+    blob_to_field_elements, evaluate_blob_at, field_elements_to_blob, reduce_blob, validate_blob,
+    Fr,
+};
+// The process-wide trusted setup.
+pub use global::{install_trusted_setup, trusted_setup, trusted_setup_source, TrustedSetupSource};
+// Sidecar inclusion proofs, which need the ssz hashing.
+#[cfg(feature = "ssz")]
+pub use inclusion::{verify_kzg_commitment_inclusion_proof, Fork};
 use serde::Deserialize;
 
+/// The mainnet trusted setup. It does not load under the `minimal-spec` preset.
 pub const BUILDIN_TRUSTED_SETUP: &[u8] = include_bytes!("trusted_setup.json");
 
+/// The JSON trusted setup. The EIP-4844 layout only has `g1_lagrange` and `g2_monomial`; newer
+/// setups also carry `g1_monomial`, without which the cell functions fail.
 #[derive(Deserialize)]
 struct TrustedSetup {
+    g1_monomial: Option<Vec<String>>,
     g1_lagrange: Vec<String>,
     g2_monomial: Vec<String>,
 }
 
-pub fn build_setting(json_bytes: &[u8]) -> Result<KzgSettings, String> {
-    let setup: TrustedSetup =
-        serde_json::from_slice(json_bytes).map_err(|err| format!("{:?}", err))?;
-
-    if setup.g1_lagrange.len() != FIELD_ELEMENTS_PER_BLOB {
-        return Err(format!(
-            "Invalid number of g1 points in trusted setup. Expected {} got {}",
-            FIELD_ELEMENTS_PER_BLOB,
-            setup.g1_lagrange.len(),
-        ));
-    }
-    if setup.g2_monomial.len() != NUM_G2_POINTS {
-        return Err(format!(
-            "Invalid number of g2 points in trusted setup. Expected {} got {}",
-            NUM_G2_POINTS,
-            setup.g2_monomial.len(),
-        ));
+/// Decodes hex-encoded compressed points into one contiguous buffer, checking that each one is
+/// in the prime-order subgroup.
+fn decode_points(
+    name: &str,
+    items: &[String],
+    expected: usize,
+    point_len: usize,
+    in_subgroup: fn(&[u8]) -> bool,
+) -> Result<Vec<u8>, Error> {
+    if items.len() != expected {
+        return Err(Error::InvalidTrustedSetup(format!(
+            "Invalid number of {} points in trusted setup. Expected {} got {}",
+            name,
+            expected,
+            items.len(),
+        )));
     }
 
-    let mut g1 = vec![0_u8; setup.g1_lagrange.len() * BYTES_PER_G1_POINT];
-    let mut g2 = vec![0_u8; setup.g2_monomial.len() * BYTES_PER_G2_POINT];
-    for (idx, item) in setup.g1_lagrange.iter().enumerate() {
-        let mut item = item.trim_start_matches("0x");
-        let g1_bytes = hex::decode(item).unwrap();
-        if g1_bytes.len() != BYTES_PER_G1_POINT {
-            return Err(format!(
-                "invalid g1 bytes={}, expected {}",
-                item, BYTES_PER_G1_POINT
-            ));
+    let mut out = Vec::with_capacity(items.len() * point_len);
+    for (idx, item) in items.iter().enumerate() {
+        let bytes = bindings::hex_to_bytes(item)?;
+        if bytes.len() != point_len {
+            return Err(Error::InvalidTrustedSetup(format!(
+                "Invalid {} point {}. Expected {} bytes got {}",
+                name,
+                idx,
+                point_len,
+                bytes.len(),
+            )));
         }
-        g1[idx * BYTES_PER_G1_POINT..(idx + 1) * BYTES_PER_G1_POINT].copy_from_slice(&g1_bytes);
-    }
-    for (idx, item) in setup.g2_monomial.iter().enumerate() {
-        let mut item = item.trim_start_matches("0x");
-        let g2_bytes = hex::decode(item).unwrap();
-        if g2_bytes.len() != BYTES_PER_G2_POINT {
-            return Err(format!(
-                "invalid g2 bytes={}, expected {}",
-                item, BYTES_PER_G2_POINT
-            ));
+        if !in_subgroup(&bytes) {
+            return Err(Error::InvalidTrustedSetup(format!(
+                "Invalid {} point {}: not in the prime-order subgroup",
+                name, idx,
+            )));
         }
-        g2[idx * BYTES_PER_G2_POINT..(idx + 1) * BYTES_PER_G2_POINT].copy_from_slice(&g2_bytes);
+        out.extend_from_slice(&bytes);
     }
+    Ok(out)
+}
+
+pub fn build_setting(json_bytes: &[u8]) -> Result<KzgSettings, Error> {
+    let setup: TrustedSetup = serde_json::from_slice(json_bytes).map_err(|err| {
+        Error::InvalidTrustedSetup(format!("Invalid trusted setup JSON: {}", err))
+    })?;
 
+    let g1_in_subgroup: fn(&[u8]) -> bool = |b| G1Point::from_compressed(b).is_ok();
+    let g2_in_subgroup: fn(&[u8]) -> bool = |b| G2Point::from_compressed(b).is_ok();
+    let g1_monomial = match &setup.g1_monomial {
+        Some(points) => Some(decode_points(
+            "g1 monomial",
+            points,
+            FIELD_ELEMENTS_PER_BLOB,
+            BYTES_PER_G1_POINT,
+            g1_in_subgroup,
+        )?),
+        None => None,
+    };
+    let g1 = decode_points(
+        "g1",
+        &setup.g1_lagrange,
+        FIELD_ELEMENTS_PER_BLOB,
+        BYTES_PER_G1_POINT,
+        g1_in_subgroup,
+    )?;
+    let g2 = decode_points(
+        "g2",
+        &setup.g2_monomial,
+        NUM_G2_POINTS,
+        BYTES_PER_G2_POINT,
+        g2_in_subgroup,
+    )?;
+
+    let g1_monomial_ptr = g1_monomial
+        .as_ref()
+        .map_or(core::ptr::null(), |g1| g1.as_ptr());
     let mut kzg_settings = MaybeUninit::<KzgSettings>::uninit();
     unsafe {
         let res = crate::bindings::load_trusted_setup(
             kzg_settings.as_mut_ptr(),
+            g1_monomial_ptr,
             g1.as_ptr(),
-            setup.g1_lagrange.len(),
+            FIELD_ELEMENTS_PER_BLOB,
             g2.as_ptr(),
-            setup.g2_monomial.len(),
+            NUM_G2_POINTS,
         );
         if let crate::bindings::C_KZG_RET::C_KZG_OK = res {
             Ok(kzg_settings.assume_init())
         } else {
-            Err(format!("Invalid trusted setup: {:?}", res))
+            Err(Error::InvalidTrustedSetup(format!(
+                "Invalid trusted setup: {:?}",
+                res
+            )))
         }
     }
 }
 
 lazy_static::lazy_static! {
+    /// The bundled setup, loaded on first use. It panics if the setup fails to load and ignores
+    /// [`install_trusted_setup`]; prefer [`trusted_setup`].
     pub static ref BUILDIN_TRUSTED_SETTING: KzgSettings = build_setting(&BUILDIN_TRUSTED_SETUP).unwrap();
 }
+
+#[cfg(test)]
+#[cfg(not(feature = "minimal-spec"))]
+mod tests {
+    use super::*;
+    use serde_json::{Map, Value};
+
+    /// The bundled setup JSON after `edit`.
+    fn edited_setup(edit: impl FnOnce(&mut Map<String, Value>)) -> Vec<u8> {
+        let mut setup: Value = serde_json::from_slice(BUILDIN_TRUSTED_SETUP).unwrap();
+        edit(setup.as_object_mut().unwrap());
+        serde_json::to_vec(&setup).unwrap()
+    }
+
+    /// Replaces point `idx` of the `key` list.
+    fn replace_point(key: &str, idx: usize, point: &str) -> Vec<u8> {
+        edited_setup(|setup| setup[key][idx] = Value::from(point))
+    }
+
+    /// The hex of a flagged compressed G1 point with the given x.
+    fn g1_with_x(x: u8) -> String {
+        format!("0x80{}{:02x}", "00".repeat(46), x)
+    }
+
+    /// The hex of a flagged compressed G2 point whose x has the given imaginary part and a zero
+    /// real part.
+    fn g2_with_x_imaginary(x: u8) -> String {
+        format!("0x80{}{:02x}{}", "00".repeat(46), x, "00".repeat(48))
+    }
+
+    #[test]
+    fn test_build_setting_rejects_malformed_hex() {
+        let setup: TrustedSetup = serde_json::from_slice(BUILDIN_TRUSTED_SETUP).unwrap();
+        let point = &setup.g1_lagrange[5];
+        let bad_digit = format!("0xzz{}", &point[4..]);
+        let odd_length = &point[..point.len() - 1];
+        for json in [
+            replace_point("g1_lagrange", 5, &bad_digit),
+            replace_point("g1_lagrange", 5, odd_length),
+            replace_point("g1_monomial", 0, &bad_digit),
+            replace_point("g1_monomial", 0, odd_length),
+        ] {
+            assert!(matches!(
+                build_setting(&json),
+                Err(Error::InvalidHexFormat(_))
+            ));
+        }
+        // Valid hex of the wrong length is a bad setup rather than bad hex.
+        assert!(matches!(
+            build_setting(&replace_point("g2_monomial", 64, "0x")),
+            Err(Error::InvalidTrustedSetup(_))
+        ));
+        assert!(matches!(
+            build_setting(&BUILDIN_TRUSTED_SETUP[1..]),
+            Err(Error::InvalidTrustedSetup(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_setting_rejects_points_outside_subgroup() {
+        // x = 1 is not on G1 and x = 4 is on the curve but outside the subgroup; x = 0 is not on
+        // G2 and x = i is on the curve but outside the subgroup.
+        for json in [
+            replace_point("g1_lagrange", 0, &g1_with_x(1)),
+            replace_point("g1_lagrange", 7, &g1_with_x(4)),
+            replace_point("g1_monomial", 3, &g1_with_x(4)),
+            replace_point("g2_monomial", 0, &g2_with_x_imaginary(0)),
+            replace_point("g2_monomial", 1, &g2_with_x_imaginary(1)),
+        ] {
+            match build_setting(&json) {
+                Err(Error::InvalidTrustedSetup(msg)) => {
+                    assert!(msg.contains("not in the prime-order subgroup"), "{}", msg)
+                }
+                other => panic!("unexpected result {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_build_setting_monomial_points() {
+        let setup: TrustedSetup = serde_json::from_slice(BUILDIN_TRUSTED_SETUP).unwrap();
+        let expected: Vec<Vec<u8>> = setup
+            .g1_monomial
+            .unwrap()
+            .iter()
+            .map(|point| bindings::hex_to_bytes(point).unwrap())
+            .collect();
+        let bundled = build_setting(BUILDIN_TRUSTED_SETUP).unwrap();
+        let points = bindings::g1_monomial_bytes(&bundled);
+        assert_eq!(points.len(), expected.len());
+        assert!(points.iter().zip(&expected).all(|(p, e)| p[..] == e[..]));
+
+        // The EIP-4844 layout still loads, but only for the blob functions.
+        let eip4844 = build_setting(&edited_setup(|setup| {
+            setup.remove("g1_monomial");
+        }))
+        .unwrap();
+        let blob = Blob::new([0; BYTES_PER_BLOB]);
+        assert_eq!(
+            KzgCommitment::blob_to_kzg_commitment(&blob, &eip4844).unwrap(),
+            KzgCommitment::blob_to_kzg_commitment(&blob, &bundled).unwrap()
+        );
+        assert!(matches!(
+            KzgProof::compute_cells_and_kzg_proofs(&blob, &eip4844),
+            Err(Error::CError(CkzgError::C_KZG_BADARGS))
+        ));
+    }
+}
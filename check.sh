@@ -8,3 +8,15 @@ ls */Cargo.toml | awk -F/ '{print $1}' | while read n; do
 	cargo build --no-default-features --features tstd
 	cd ../
 done
+
+# The optional blst features pull in extra code paths that the loop above
+# doesn't build.
+cd blst
+cargo build --features serde-std,parallel-std
+cargo build --no-default-features --features tstd,serde-tstd,parallel-tstd
+cd ../
+
+# The batch APIs are threaded under std unless no-threads is set.
+cd c-kzg
+cargo build --features no-threads
+cd ../
@@ -0,0 +1,92 @@
+use std::prelude::v1::*;
+
+use super::{Encode, Error, Decode, Compact, CompactRef, Codec};
+use blst::{min_pk, min_sig};
+use generic_array::GenericArray;
+use std::vec::Vec;
+
+// BLS types are encoded as their compressed bytes. Decoding goes through the
+// type's own `from_bytes`, so points are checked to be on the curve and in the
+// right subgroup, and zero secret keys are rejected.
+macro_rules! ssz_bls {
+	($t:ty, $count:ty, $to_bytes:ident) => {
+		impl Codec for $t {
+			type Size = <Compact<GenericArray<u8, $count>> as Codec>::Size;
+		}
+
+		impl Encode for $t {
+			fn encode(&self) -> Vec<u8> {
+				CompactRef(GenericArray::<u8, $count>::from_slice(&self.$to_bytes()[..])).encode()
+			}
+		}
+
+		impl Decode for $t {
+			fn decode(value: &[u8]) -> Result<Self, Error> {
+				let decoded = Compact::<GenericArray<u8, $count>>::decode(value)?;
+				<$t>::from_bytes(decoded.0.as_slice()).map_err(|_| Error::InvalidType)
+			}
+		}
+	};
+}
+
+ssz_bls!(min_pk::PublicKey, typenum::U48, compress);
+ssz_bls!(min_pk::Signature, typenum::U96, compress);
+ssz_bls!(min_pk::SecretKey, typenum::U32, to_bytes);
+ssz_bls!(min_sig::PublicKey, typenum::U96, compress);
+ssz_bls!(min_sig::Signature, typenum::U48, compress);
+ssz_bls!(min_sig::SecretKey, typenum::U32, to_bytes);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn roundtrip<T: Encode + Decode + PartialEq + core::fmt::Debug>(value: &T, len: usize) {
+		let encoded = value.encode();
+		assert_eq!(encoded.len(), len);
+		assert_eq!(&T::decode(&encoded).unwrap(), value);
+		assert!(T::decode(&encoded[1..]).is_err());
+		assert!(T::decode(&[&encoded[..], &[0]].concat()).is_err());
+	}
+
+	// Flagged compressed G1 points with x = 1, which is off the curve, and
+	// x = 4, which is on it but outside the subgroup.
+	fn invalid_g1() -> [Vec<u8>; 2] {
+		[1, 4].map(|x| [&[0x80][..], &[0; 46], &[x]].concat())
+	}
+
+	// The same for G2 with x = 0, off the curve, and x = i, on it but outside
+	// the subgroup. The imaginary half of x comes first.
+	fn invalid_g2() -> [Vec<u8>; 2] {
+		[0, 1].map(|x| [&[0x80][..], &[0; 46], &[x], &[0; 48]].concat())
+	}
+
+	#[test]
+	fn test_min_pk() {
+		let sk = min_pk::keygen_v3([1; 32]);
+		roundtrip(&sk.public(), 48);
+		roundtrip(&min_pk::sign(&sk, b"ssz"), 96);
+		roundtrip(&sk, 32);
+		assert!(matches!(min_pk::SecretKey::decode(&[0; 32]), Err(Error::InvalidType)));
+		for bytes in invalid_g1() {
+			assert!(matches!(min_pk::PublicKey::decode(&bytes), Err(Error::InvalidType)));
+		}
+		for bytes in invalid_g2() {
+			assert!(matches!(min_pk::Signature::decode(&bytes), Err(Error::InvalidType)));
+		}
+	}
+
+	#[test]
+	fn test_min_sig() {
+		let sk = min_sig::keygen_v3([2; 32]);
+		roundtrip(&sk.public(), 96);
+		roundtrip(&min_sig::sign(&sk, b"ssz"), 48);
+		roundtrip(&sk, 32);
+		assert!(matches!(min_sig::SecretKey::decode(&[0; 32]), Err(Error::InvalidType)));
+		for bytes in invalid_g2() {
+			assert!(matches!(min_sig::PublicKey::decode(&bytes), Err(Error::InvalidType)));
+		}
+		for bytes in invalid_g1() {
+			assert!(matches!(min_sig::Signature::decode(&bytes), Err(Error::InvalidType)));
+		}
+	}
+}
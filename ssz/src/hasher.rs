@@ -2,6 +2,7 @@ use std::prelude::v1::*;
 
 use super::Error;
 
+use blst::sha256::Sha256;
 use lazy_static::lazy_static;
 use std::io::Write;
 
@@ -65,7 +66,10 @@ impl Hasher {
     }
 
     fn do_hash(a: &[u8], b: &[u8]) -> [u8; 32] {
-        sha256_sum(&[a, b].concat())
+        let mut h = Sha256::new();
+        h.update(a);
+        h.update(b);
+        h.finalize()
     }
 
     fn next_power_of_two(mut v: usize) -> usize {
@@ -32,6 +32,7 @@ mod size;
 mod fixed;
 mod variable;
 mod hasher;
+mod bls;
 
 pub use bm_le::{Compact, CompactRef, MaxVec};
 pub use series::{Series, SeriesItem};